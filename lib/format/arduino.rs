@@ -0,0 +1,187 @@
+use std::io::{Result, Write, Error, ErrorKind};
+
+use crate::{SPWM, DutyCycle, Alignment};
+
+use super::{Format, UserSection, FormatArgs, Language, CInt};
+
+/// Timer1 clock prescalers of ATmega328P and their clock select bits
+const PRESCALERS: [(u32, &str); 5] = [
+    (1, "_BV(CS10)"),
+    (8, "_BV(CS11)"),
+    (64, "_BV(CS11) | _BV(CS10)"),
+    (256, "_BV(CS12)"),
+    (1024, "_BV(CS12) | _BV(CS10)"),
+];
+
+/// Arduino/AVR header with `PROGMEM` tables and inline accessors reading them from flash
+#[derive(Default)]
+pub struct ArduinoFile;
+
+impl Format for ArduinoFile {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let ty = CInt::from_top(spwm.pwm_top());
+        let name = args.ident(spwm, Language::C);
+
+        writeln!(buf, "#pragma once\n")?;
+        writeln!(buf, "#include <stdint.h>")?;
+        writeln!(buf, "#include <avr/pgmspace.h>\n\n")?;
+        sections.write(0, buf)?;
+        writeln!(buf, "#define  {}_CARRIER_FREQ    {}", name, spwm.carrier_freq())?;
+        writeln!(buf, "#define  {}_SIN_FREQ        {}", name, spwm.sin_freq())?;
+        writeln!(buf, "#define  {}_TOP             {}", name, spwm.pwm_top())?;
+        writeln!(buf, "#define  {}_LEN             {}\n", name, table.len())?;
+        sections.write(1, buf)?;
+
+        write_table(buf, ty, &name, &name, &table, args.row_width()?)?;
+        sections.write(2, buf)?;
+        if let Some(pad) = args.inverter {
+            let table = spwm.table_not(&table, pad);
+            write_table(buf, ty, &format!("{}_NOT", name), &name, &table, args.row_width()?)?;
+        }
+        sections.write(3, buf)?;
+        sections.write_remains(4, buf)?;
+
+        Ok(())
+    }
+}
+
+fn write_table(buf: &mut dyn Write, ty: CInt, name: &str, len: &str, table: &[DutyCycle], width: usize) -> Result<()> {
+    let read = match ty.name {
+        "uint8_t" => "pgm_read_byte",
+        "uint16_t" => "pgm_read_word",
+        _ => "pgm_read_dword",
+    };
+
+    writeln!(buf, "const {} {}[{}_LEN] PROGMEM = {{", ty.name, name, len)?;
+    for row in table.chunks(width) {
+        write!(buf, "    ")?;
+        for val in row {
+            write!(buf, "{:width$}, ", val, width=ty.digits)?;
+        }
+        writeln!(buf)?;
+    }
+    writeln!(buf, "}};\n")?;
+
+    writeln!(buf, "static inline {} {}_read(uint16_t index) {{", ty.name, name)?;
+    writeln!(buf, "    return {}(&{}[index]);", read, name)?;
+    writeln!(buf, "}}\n\n")
+}
+
+/// return prescaler, clock select bits and TOP of Timer1 for carrier frequency
+fn timer1(f_cpu: u32, carrier_freq: f64, align: Alignment) -> Option<(u32, &'static str, u32)> {
+    PRESCALERS.iter().find_map(|(prescaler, bits)| {
+        let ticks = f_cpu as f64 / (*prescaler as f64 * carrier_freq);
+        // fast PWM period is TOP + 1 ticks, phase and frequency correct PWM period is 2 * TOP ticks
+        let top = match align {
+            Alignment::Edge => ticks.round() - 1.0,
+            Alignment::Center => (ticks / 2.0).round(),
+        };
+        (top >= 1.0 && top <= u16::MAX as f64).then_some((*prescaler, *bits, top as u32))
+    })
+}
+
+/// Arduino sketch including header of [`ArduinoFile`] and playing table on Timer1 OC1A (pin 9),
+/// complement is played on OC1B (pin 10) with inverted output when inverter is set.
+/// OC1B is compared dead time (inverter pad) after OC1A so pin 10 is never high together with pin 9
+#[derive(Default)]
+pub struct ArduinoSketch;
+
+impl Format for ArduinoSketch {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let name = args.ident(spwm, Language::C);
+        let include = args.include.clone().unwrap_or_else(|| format!("{}.h", name.to_lowercase()));
+        write_sketch(spwm, buf, sections, args, &name, &include)
+    }
+}
+
+fn write_sketch(spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs, name: &str, include: &str) -> Result<()> {
+    let (prescaler, bits, top) = timer1(args.f_cpu, spwm.carrier_freq(), args.align)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "carrier frequency is out of Timer1 range"))?;
+    let sample = |table: &str| if top == spwm.pwm_top() as u32 {
+        format!("{}_read(sample_index)", table)
+    }
+    else {
+        format!("(uint16_t)((uint32_t){}_read(sample_index) * TIMER_TOP / {}_TOP)", table, name)
+    };
+    // timer ticks of dead time, rounded up
+    let dead_ticks = args.inverter.map(|pad| (pad.max(0.0) * args.f_cpu as f64 / prescaler as f64).ceil() as u32);
+
+    writeln!(buf, "// play {} on Timer1, OC1A (pin 9){}", name, if args.inverter.is_some() { ", complement on OC1B (pin 10)" } else { "" })?;
+    writeln!(buf, "// carrier {}Hz, F_CPU {}Hz, prescaler {}, TOP {}", spwm.carrier_freq(), args.f_cpu, prescaler, top)?;
+    if dead_ticks.is_some() {
+        writeln!(buf, "// pin 10 is inverted and compared at OCR1A + DEAD_TICKS, it rises dead time after pin 9 falls")?;
+        match args.align {
+            Alignment::Edge => writeln!(buf, "// and falls when pin 9 rises at BOTTOM, use center alignment for dead time on both edges")?,
+            Alignment::Center => writeln!(buf, "// and falls dead time before pin 9 rises")?,
+        }
+    }
+    writeln!(buf)?;
+    writeln!(buf, "#include \"{}\"\n", include)?;
+    sections.write(0, buf)?;
+    writeln!(buf, "#define  TIMER_PRESCALER  {}", prescaler)?;
+    writeln!(buf, "#define  TIMER_TOP        {}", top)?;
+    if let Some(ticks) = dead_ticks {
+        writeln!(buf, "#define  DEAD_TICKS       {}", ticks)?;
+    }
+    writeln!(buf)?;
+    writeln!(buf, "static volatile uint16_t sample_index = 0;\n")?;
+    if dead_ticks.is_some() {
+        writeln!(buf, "// compare value of pin 10, clamped to TOP where pin 10 stays low")?;
+        writeln!(buf, "static inline uint16_t dead_time_compare(uint16_t duty) {{")?;
+        writeln!(buf, "    uint32_t val = (uint32_t)duty + DEAD_TICKS;")?;
+        writeln!(buf, "    return val < TIMER_TOP ? val : TIMER_TOP;")?;
+        writeln!(buf, "}}\n")?;
+    }
+
+    writeln!(buf, "ISR(TIMER1_OVF_vect) {{")?;
+    writeln!(buf, "    if (++sample_index >= {}_LEN) {{", name)?;
+    writeln!(buf, "        sample_index = 0;")?;
+    writeln!(buf, "    }}")?;
+    writeln!(buf, "    uint16_t duty = {};", sample(name))?;
+    writeln!(buf, "    OCR1A = duty;")?;
+    if dead_ticks.is_some() {
+        writeln!(buf, "    OCR1B = dead_time_compare(duty);")?;
+    }
+    sections.write(1, buf)?;
+    writeln!(buf, "}}\n")?;
+
+    writeln!(buf, "void setup() {{")?;
+    writeln!(buf, "    pinMode(9, OUTPUT);")?;
+    if args.inverter.is_some() {
+        writeln!(buf, "    pinMode(10, OUTPUT);")?;
+    }
+    writeln!(buf, "    TCCR1A = 0;")?;
+    writeln!(buf, "    TCCR1B = 0;")?;
+    writeln!(buf, "    TCNT1 = 0;")?;
+    writeln!(buf, "    ICR1 = TIMER_TOP;")?;
+    writeln!(buf, "    uint16_t duty = {};", sample(name))?;
+    writeln!(buf, "    OCR1A = duty;")?;
+    let com = if dead_ticks.is_some() {
+        writeln!(buf, "    OCR1B = dead_time_compare(duty);")?;
+        "_BV(COM1A1) | _BV(COM1B1) | _BV(COM1B0)"
+    }
+    else {
+        "_BV(COM1A1)"
+    };
+    match args.align {
+        Alignment::Edge => {
+            writeln!(buf, "    // fast PWM, TOP is ICR1")?;
+            writeln!(buf, "    TCCR1A = {} | _BV(WGM11);", com)?;
+            writeln!(buf, "    TCCR1B = _BV(WGM13) | _BV(WGM12) | {};", bits)?;
+        },
+        Alignment::Center => {
+            writeln!(buf, "    // phase and frequency correct PWM, TOP is ICR1")?;
+            writeln!(buf, "    TCCR1A = {};", com)?;
+            writeln!(buf, "    TCCR1B = _BV(WGM13) | {};", bits)?;
+        },
+    }
+    writeln!(buf, "    TIMSK1 = _BV(TOIE1);")?;
+    sections.write(2, buf)?;
+    writeln!(buf, "}}\n")?;
+
+    writeln!(buf, "void loop() {{")?;
+    sections.write(3, buf)?;
+    writeln!(buf, "}}\n")?;
+    sections.write_remains(4, buf)
+}
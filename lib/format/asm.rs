@@ -0,0 +1,181 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs, Language, WordWidth};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Gnu,
+    Arm,
+    Avr,
+}
+
+impl Dialect {
+    fn comment(&self) -> &'static str {
+        match self {
+            Dialect::Gnu => "#",
+            Dialect::Arm | Dialect::Avr => ";",
+        }
+    }
+
+    fn directive(&self, width: WordWidth) -> &'static str {
+        match (self, width) {
+            (Dialect::Gnu, WordWidth::U8) => ".byte",
+            (Dialect::Gnu, WordWidth::U16) => ".hword",
+            (Dialect::Gnu, WordWidth::U32) => ".word",
+            (Dialect::Arm, WordWidth::U8) => "DCB",
+            (Dialect::Arm, WordWidth::U16) => "DCW",
+            (Dialect::Arm, WordWidth::U32) => "DCD",
+            (Dialect::Avr, WordWidth::U8) => ".byte",
+            (Dialect::Avr, WordWidth::U16) => ".word",
+            (Dialect::Avr, WordWidth::U32) => ".long",
+        }
+    }
+}
+
+/// GNU as data directives, `# ...` comment lines are accepted by every target
+#[derive(Default)]
+pub struct GnuAsm;
+
+impl Format for GnuAsm {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        Some(Dialect::Gnu.comment())
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        write_asm(spwm, buf, sections, args, Dialect::Gnu)
+    }
+}
+
+/// ARM armasm data directives inside an `AREA`
+#[derive(Default)]
+pub struct ArmAsm;
+
+impl Format for ArmAsm {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        Some(Dialect::Arm.comment())
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        write_asm(spwm, buf, sections, args, Dialect::Arm)
+    }
+}
+
+/// GNU avr-as data directives, tables are placed in `.progmem.data` flash section by default
+#[derive(Default)]
+pub struct AvrAsm;
+
+impl Format for AvrAsm {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        Some(Dialect::Avr.comment())
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        write_asm(spwm, buf, sections, args, Dialect::Avr)
+    }
+}
+
+fn write_asm(spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs, dialect: Dialect) -> Result<()> {
+    let table = spwm.lookup_table();
+    let name = args.ident(spwm, Language::C);
+    let width = args.word_width(spwm)?;
+    let comment = dialect.comment();
+
+    writeln!(buf, "{} {} {}Hz, carrier {}Hz, pwm top {}\n", comment, args.name, spwm.sin_freq(), spwm.carrier_freq(), spwm.pwm_top())?;
+    sections.write(0, buf)?;
+    match dialect {
+        Dialect::Gnu | Dialect::Avr => {
+            // avr-gcc keeps flash tables in .progmem.data
+            let (section, flags) = match dialect {
+                Dialect::Avr => (".progmem.data", ",@progbits"),
+                _ => (".rodata", ""),
+            };
+            writeln!(buf, "    .section {},\"a\"{}", args.asm_section.as_deref().unwrap_or(section), flags)?;
+            writeln!(buf, "    .balign {}\n", width.bytes())?;
+            writeln!(buf, "    .global {}", name)?;
+            if args.inverter.is_some() {
+                writeln!(buf, "    .global {}_NOT", name)?;
+            }
+            writeln!(buf, "    .global {}_LEN", name)?;
+            writeln!(buf, "    .set {}_LEN, {}\n", name, table.len())?;
+        },
+        Dialect::Arm => {
+            let section = args.asm_section.as_deref().unwrap_or(".rodata");
+            let section = if section.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                section.to_string()
+            }
+            else {
+                format!("|{}|", section)
+            };
+            writeln!(buf, "    AREA    {}, DATA, READONLY, ALIGN={}\n", section, width.bytes().trailing_zeros())?;
+            writeln!(buf, "    EXPORT  {}", name)?;
+            if args.inverter.is_some() {
+                writeln!(buf, "    EXPORT  {}_NOT", name)?;
+            }
+            writeln!(buf, "    EXPORT  {}_LEN\n", name)?;
+            writeln!(buf, "{}_LEN EQU {}\n", name, table.len())?;
+        },
+    }
+
+    write_table(buf, dialect, width, &name, &table, args.row_width()?)?;
+    sections.write(1, buf)?;
+    if let Some(pad) = args.inverter {
+        let table = spwm.table_not(&table, pad);
+        write_table(buf, dialect, width, &format!("{}_NOT", name), &table, args.row_width()?)?;
+    }
+    sections.write(2, buf)?;
+    sections.write_remains(3, buf)?;
+    if dialect == Dialect::Arm {
+        writeln!(buf, "    END")?;
+    }
+
+    Ok(())
+}
+
+fn write_table(buf: &mut dyn Write, dialect: Dialect, width: WordWidth, name: &str, table: &[DutyCycle], row_width: usize) -> Result<()> {
+    match dialect {
+        Dialect::Gnu | Dialect::Avr => writeln!(buf, "{}:", name)?,
+        Dialect::Arm => writeln!(buf, "{}", name)?,
+    }
+    for row in table.chunks(row_width) {
+        let row: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+        writeln!(buf, "    {} {}", dialect.directive(width), row.join(", "))?;
+    }
+    writeln!(buf)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avr_output() {
+        let spwm = SPWM::new(50.0, 3, 0.00667, 1000, 0);
+        let args = FormatArgs {
+            inverter: Some(0.0),
+            ..Default::default()
+        };
+        let table = spwm.lookup_table();
+        let row = |table: &[DutyCycle]| table.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+
+        let mut buf = Vec::new();
+        AvrAsm.write(&spwm, &mut buf, &UserSection::new(";"), &args).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = text.lines().filter(|line| !line.is_empty() && !line.starts_with(';')).collect();
+
+        assert_eq!(lines, vec![
+            "    .section .progmem.data,\"a\",@progbits",
+            "    .balign 2",
+            "    .global WAVE_50HZ",
+            "    .global WAVE_50HZ_NOT",
+            "    .global WAVE_50HZ_LEN",
+            "    .set WAVE_50HZ_LEN, 3",
+            "WAVE_50HZ:",
+            &format!("    .word {}", row(&table)),
+            "WAVE_50HZ_NOT:",
+            &format!("    .word {}", row(&spwm.table_not(&table, 0.0))),
+        ]);
+    }
+}
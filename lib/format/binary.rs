@@ -0,0 +1,134 @@
+use std::io::{Result, Write};
+
+use crate::SPWM;
+
+use super::{Format, UserSection, FormatArgs};
+
+const MAGIC: &[u8; 4]       = b"SPWM";
+const FLAG_INVERTER: u8     = 0x01;
+
+/// Packed binary table, optionally prefixed with a 24 bytes header:
+///
+/// | offset | size | field                                   |
+/// |--------|------|-----------------------------------------|
+/// | 0      | 4    | magic `SPWM`                            |
+/// | 4      | 1    | word width in bytes                     |
+/// | 5      | 1    | flags, bit 0 is inverter table present  |
+/// | 6      | 2    | reserved                                |
+/// | 8      | 4    | number of samples (u32)                 |
+/// | 12     | 4    | carrier frequency (f32)                 |
+/// | 16     | 4    | sin frequency (f32)                     |
+/// | 20     | 4    | CRC-32 of data after header             |
+///
+/// Header fields use same endianness as data, inverter table is placed right after the table
+#[derive(Default)]
+pub struct Binary;
+
+impl Format for Binary {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let data = args.pack_tables(spwm)?;
+
+        if args.header {
+            let u32_bytes = |val: u32| args.endian.u32_bytes(val);
+            let flags = if args.inverter.is_some() { FLAG_INVERTER } else { 0 };
+
+            buf.write_all(MAGIC)?;
+            buf.write_all(&[args.word_width(spwm)?.bytes() as u8, flags, 0, 0])?;
+            buf.write_all(&u32_bytes(spwm.num_of_samples() as u32))?;
+            buf.write_all(&u32_bytes((spwm.carrier_freq() as f32).to_bits()))?;
+            buf.write_all(&u32_bytes((spwm.sin_freq() as f32).to_bits()))?;
+            buf.write_all(&u32_bytes(crc32(&data)))?;
+        }
+        buf.write_all(&data)?;
+
+        Ok(())
+    }
+}
+
+/// CRC-32 (IEEE 802.3), same as zlib
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::{Endian, WordWidth};
+
+    fn binary(spwm: &SPWM, args: &FormatArgs) -> Vec<u8> {
+        let mut buf = Vec::new();
+        Binary.write(spwm, &mut buf, &UserSection::new(""), args).unwrap();
+        buf
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn header_layout() {
+        let spwm = SPWM::new(50.0, 4, 0.005, 1000, 0);
+        let args = FormatArgs {
+            header: true,
+            inverter: Some(0.0),
+            endian: Endian::Big,
+            ..Default::default()
+        };
+
+        let buf = binary(&spwm, &args);
+        let data = args.pack_tables(&spwm).unwrap();
+        assert_eq!(buf.len(), 24 + data.len());
+        assert_eq!(&buf[..8], b"SPWM\x02\x01\x00\x00");
+        assert_eq!(buf[8..12], 4u32.to_be_bytes());
+        assert_eq!(buf[12..16], 200f32.to_be_bytes());
+        assert_eq!(buf[16..20], 50f32.to_be_bytes());
+        assert_eq!(buf[20..24], crc32(&data).to_be_bytes());
+        assert_eq!(buf[24..], data);
+    }
+
+    #[test]
+    fn word_packing() {
+        let spwm = SPWM::new(50.0, 4, 0.005, 1000, 0);
+        let table = spwm.lookup_table();
+        let cases = [
+            (WordWidth::U16, Endian::Little, table.iter().flat_map(|v| (*v as u16).to_le_bytes()).collect::<Vec<_>>()),
+            (WordWidth::U16, Endian::Big, table.iter().flat_map(|v| (*v as u16).to_be_bytes()).collect()),
+            (WordWidth::U32, Endian::Little, table.iter().flat_map(|v| (*v as u32).to_le_bytes()).collect()),
+            (WordWidth::U32, Endian::Big, table.iter().flat_map(|v| (*v as u32).to_be_bytes()).collect()),
+        ];
+        for (word_width, endian, expected) in cases {
+            let args = FormatArgs {
+                word_width: Some(word_width),
+                endian,
+                ..Default::default()
+            };
+            assert_eq!(binary(&spwm, &args), expected);
+        }
+
+        // 500 is 0x01F4
+        let spwm = SPWM::new(50.0, 1, 0.02, 1000, 0);
+        assert_eq!(spwm.lookup_table(), vec![500]);
+        let args = FormatArgs::default();
+        assert_eq!(binary(&spwm, &args), vec![0xF4, 0x01]);
+        let args = FormatArgs {
+            word_width: Some(WordWidth::U32),
+            endian: Endian::Big,
+            ..Default::default()
+        };
+        assert_eq!(binary(&spwm, &args), vec![0x00, 0x00, 0x01, 0xF4]);
+    }
+}
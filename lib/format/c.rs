@@ -0,0 +1,144 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs, Language, sanitize};
+
+
+/// Fixed width integer type of C family formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CInt {
+    /// `<stdint.h>` type name
+    pub name:           &'static str,
+    /// max number of decimal digits
+    pub digits:         usize,
+    /// number of hex digits
+    pub hex_digits:     usize,
+}
+
+impl CInt {
+    /// smallest type that holds pwm top
+    pub fn from_top(pwm_top: DutyCycle) -> Self {
+        if pwm_top >= 65536 {
+            Self { name: "uint32_t", digits: 6, hex_digits: 8 }
+        }
+        else if pwm_top >= 256 {
+            Self { name: "uint16_t", digits: 5, hex_digits: 4 }
+        }
+        else {
+            Self { name: "uint8_t", digits: 3, hex_digits: 2 }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CFile;
+
+impl Format for CFile {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let ty = CInt::from_top(spwm.pwm_top());
+
+        write_c(spwm, buf, sections, args, ty.name, |val| format!("{:width$}", val, width=ty.digits))
+    }
+}
+
+#[derive(Default)]
+pub struct CHexFile;
+
+impl Format for CHexFile {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let ty = CInt::from_top(spwm.pwm_top());
+
+        write_c(spwm, buf, sections, args, ty.name, |val| format!("0x{:0width$X}", val, width=ty.hex_digits))
+    }
+}
+
+/// write single file, or source file including header when split mode is enabled
+fn write_c(spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs, ty: &str, value: impl Fn(DutyCycle) -> String) -> Result<()> {
+    let table = spwm.lookup_table();
+    let table_not = args.inverter.map(|pad| spwm.table_not(&table, pad));
+    let name = args.ident(spwm, Language::C);
+
+    if let Some(header) = &args.include {
+        writeln!(buf, "#include \"{}\"\n\n", header)?;
+        sections.write(0, buf)?;
+        write_table(buf, ty, &name, &name, &table, args.row_width()?, &value)?;
+        sections.write(1, buf)?;
+        if let Some(table_not) = &table_not {
+            write_table(buf, ty, &format!("{}_NOT", name), &name, table_not, args.row_width()?, &value)?;
+        }
+        sections.write(2, buf)?;
+        sections.write_remains(3, buf)?;
+        writeln!(buf)?;
+        return Ok(());
+    }
+
+    writeln!(buf, "#include <stdint.h>\n\n")?;
+    sections.write(0, buf)?;
+    writeln!(buf, "#define  {}_CARRIER_FREQ    {}\n",
+        name,
+        spwm.carrier_freq(),
+    )?;
+    writeln!(buf, "#define  {}_LEN             {}\n",
+        name,
+        table.len(),
+    )?;
+    sections.write(1, buf)?;
+    write_table(buf, ty, &name, &name, &table, args.row_width()?, &value)?;
+    sections.write(2, buf)?;
+    if let Some(table_not) = &table_not {
+        write_table(buf, ty, &format!("{}_NOT", name), &name, table_not, args.row_width()?, &value)?;
+    }
+    sections.write(3, buf)?;
+    sections.write_remains(4, buf)?;
+    writeln!(buf)?;
+
+    Ok(())
+}
+
+/// Header of split C source with include guard, macros and extern declarations of tables,
+/// guard is derived from include file name
+#[derive(Default)]
+pub struct CHeader;
+
+impl Format for CHeader {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let ty = CInt::from_top(spwm.pwm_top());
+        let name = args.ident(spwm, Language::C);
+        let guard = match &args.include {
+            Some(header) => sanitize(&header.to_uppercase(), Language::C),
+            None => format!("{}_H", name.to_uppercase()),
+        };
+
+        writeln!(buf, "#ifndef {}", guard)?;
+        writeln!(buf, "#define {}\n", guard)?;
+        writeln!(buf, "#include <stdint.h>\n\n")?;
+        sections.write(0, buf)?;
+        writeln!(buf, "#define  {}_CARRIER_FREQ    {}", name, spwm.carrier_freq())?;
+        writeln!(buf, "#define  {}_SIN_FREQ        {}", name, spwm.sin_freq())?;
+        writeln!(buf, "#define  {}_LEN             {}\n", name, spwm.num_of_samples())?;
+        sections.write(1, buf)?;
+        writeln!(buf, "extern const {} {}[{}_LEN];", ty.name, name, name)?;
+        if args.inverter.is_some() {
+            writeln!(buf, "extern const {} {}_NOT[{}_LEN];", ty.name, name, name)?;
+        }
+        writeln!(buf)?;
+        sections.write(2, buf)?;
+        sections.write_remains(3, buf)?;
+        writeln!(buf, "#endif /* {} */", guard)
+    }
+}
+
+fn write_table(buf: &mut dyn Write, ty: &str, name: &str, len: &str, table: &[DutyCycle], width: usize, value: &impl Fn(DutyCycle) -> String) -> Result<()> {
+    writeln!(buf, "const {} {}[{}_LEN] = {{", ty, name, len)?;
+
+    for row in table.chunks(width) {
+        write!(buf, "    ")?;
+        for val in row {
+            write!(buf, "{}, ", value(*val))?;
+        }
+        writeln!(buf)?;
+    }
+
+    writeln!(buf, "}};\n\n")
+}
@@ -0,0 +1,67 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs, Language, CInt, sanitize};
+
+/// C++17 header with `inline constexpr std::array` tables inside a namespace
+#[derive(Default)]
+pub struct CppFile;
+
+impl Format for CppFile {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let ty = CInt::from_top(spwm.pwm_top());
+        let ty_name = format!("std::{}", ty.name);
+        let name = args.ident(spwm, Language::Cpp);
+        let namespace = args.namespace.split("::")
+            .map(|x| sanitize(x, Language::Cpp))
+            .collect::<Vec<_>>()
+            .join("::");
+
+        writeln!(buf, "#pragma once\n")?;
+        writeln!(buf, "#include <array>")?;
+        writeln!(buf, "#include <cstddef>")?;
+        writeln!(buf, "#include <cstdint>\n\n")?;
+        sections.write(0, buf)?;
+        writeln!(buf, "namespace {} {{\n", namespace)?;
+        writeln!(buf, "inline constexpr double {}_CARRIER_FREQ = {:?};", name, spwm.carrier_freq())?;
+        writeln!(buf, "inline constexpr double {}_SIN_FREQ = {:?};", name, spwm.sin_freq())?;
+        writeln!(buf, "inline constexpr {} {}_TOP = {};", ty_name, name, spwm.pwm_top())?;
+        writeln!(buf, "inline constexpr std::size_t {}_LEN = {};\n", name, table.len())?;
+        sections.write(1, buf)?;
+
+        write_array(buf, &ty_name, &name, &name, &table, ty.digits, args)?;
+        sections.write(2, buf)?;
+        if let Some(pad) = args.inverter {
+            let table = spwm.table_not(&table, pad);
+            write_array(buf, &ty_name, &format!("{}_NOT", name), &name, &table, ty.digits, args)?;
+        }
+        sections.write(3, buf)?;
+
+        writeln!(buf, "}} // namespace {}\n", namespace)?;
+        sections.write_remains(4, buf)?;
+
+        Ok(())
+    }
+}
+
+fn write_array(buf: &mut dyn Write, ty: &str, name: &str, len: &str, table: &[DutyCycle], digits: usize, args: &FormatArgs) -> Result<()> {
+    writeln!(buf, "inline constexpr std::array<{}, {}_LEN> {} = {{{{", ty, len, name)?;
+    for row in table.chunks(args.row_width()?) {
+        write!(buf, "    ")?;
+        for val in row {
+            write!(buf, "{:width$}, ", val, width=digits)?;
+        }
+        writeln!(buf)?;
+    }
+    writeln!(buf, "}}}};\n")?;
+
+    if args.accessor {
+        writeln!(buf, "/// return sample of {} at index, index wraps around table length", name)?;
+        writeln!(buf, "constexpr {} {}_at(std::size_t index) noexcept {{", ty, name)?;
+        writeln!(buf, "    return {}[index % {}_LEN];", name, len)?;
+        writeln!(buf, "}}\n")?;
+    }
+    Ok(())
+}
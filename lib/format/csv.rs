@@ -0,0 +1,41 @@
+use std::io::{Result, Write, BufWriter};
+
+use crate::{SPWM, SinWave};
+
+use super::{Format, UserSection, FormatArgs};
+
+/// Comma separated table with header row, columns are
+/// `index`, `time` (seconds), `reference` (sin wave, -1.0 to 1.0), `duty` and `duty_not` when inverter is set
+#[derive(Default)]
+pub struct Csv;
+
+impl Format for Csv {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let table_not = args.inverter.map(|pad| spwm.table_not(&table, pad));
+        let wave = SinWave::new(spwm.sin_freq(), 1.0);
+
+        let mut buf = BufWriter::new(buf);
+        write!(buf, "index,time,reference,duty")?;
+        if table_not.is_some() {
+            write!(buf, ",duty_not")?;
+        }
+        writeln!(buf)?;
+
+        for (idx, duty) in table.iter().enumerate() {
+            let time = idx as f64 * spwm.step();
+            write!(buf, "{},{},{},{}", idx, time, wave.sample(time), duty)?;
+            if let Some(table_not) = &table_not {
+                write!(buf, ",{}", table_not[idx])?;
+            }
+            writeln!(buf)?;
+        }
+        buf.flush()?;
+
+        Ok(())
+    }
+}
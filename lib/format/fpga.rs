@@ -0,0 +1,151 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs};
+
+/// Verilog `$readmemh` memory file
+#[derive(Default)]
+pub struct ReadMemH;
+
+/// Verilog `$readmemb` memory file
+#[derive(Default)]
+pub struct ReadMemB;
+
+/// Xilinx coefficient file
+#[derive(Default)]
+pub struct Coe;
+
+/// Intel/Altera memory initialization file
+#[derive(Default)]
+pub struct Mif;
+
+/// Memory content, inverter table is placed right after the table
+struct Memory {
+    data:               Vec<DutyCycle>,
+    /// data width in bits
+    width:              usize,
+    /// number of words in table, without padding
+    len:                usize,
+}
+
+impl Memory {
+    fn new(spwm: &SPWM, args: &FormatArgs) -> Self {
+        let table = spwm.lookup_table();
+        let mut data = table.clone();
+        if let Some(pad) = args.inverter {
+            data.extend(spwm.table_not(&table, pad));
+        }
+        let len = data.len();
+        if args.pad_pow2 {
+            data.resize(len.next_power_of_two(), 0);
+        }
+
+        Self {
+            data,
+            width: bit_width(spwm.pwm_top()),
+            len,
+        }
+    }
+
+    fn hex_digits(&self) -> usize {
+        self.width.div_ceil(4)
+    }
+}
+
+/// number of bits needed for values up to pwm top
+pub fn bit_width(pwm_top: DutyCycle) -> usize {
+    (32 - (pwm_top.max(1) as u32).leading_zeros()) as usize
+}
+
+fn header(buf: &mut dyn Write, comment: &str, spwm: &SPWM, args: &FormatArgs, mem: &Memory) -> Result<()> {
+    writeln!(buf, "{} {} {} Hz, carrier {} Hz", comment, args.name, spwm.sin_freq(), spwm.carrier_freq())?;
+    writeln!(buf, "{} width {}, depth {}, table length {}", comment, mem.width, mem.data.len(), spwm.num_of_samples())?;
+    if args.inverter.is_some() {
+        writeln!(buf, "{} inverter table start at {}", comment, spwm.num_of_samples())?;
+    }
+    if mem.data.len() > mem.len {
+        writeln!(buf, "{} padding start at {}", comment, mem.len)?;
+    }
+    Ok(())
+}
+
+impl Format for ReadMemH {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let mem = Memory::new(spwm, args);
+
+        header(buf, "//", spwm, args, &mem)?;
+        for row in mem.data.chunks(args.row_width()?) {
+            let row: Vec<String> = row.iter().map(|v| format!("{:0width$X}", v, width = mem.hex_digits())).collect();
+            writeln!(buf, "{}", row.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Format for ReadMemB {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let mem = Memory::new(spwm, args);
+
+        header(buf, "//", spwm, args, &mem)?;
+        for row in mem.data.chunks(args.row_width()?) {
+            let row: Vec<String> = row.iter().map(|v| format!("{:0width$b}", v, width = mem.width)).collect();
+            writeln!(buf, "{}", row.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Format for Coe {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let mem = Memory::new(spwm, args);
+
+        header(buf, ";", spwm, args, &mem)?;
+        writeln!(buf, "memory_initialization_radix=16;")?;
+        writeln!(buf, "memory_initialization_vector=")?;
+        let rows: Vec<String> = mem.data.chunks(args.row_width()?).map(|row| {
+            let row: Vec<String> = row.iter().map(|v| format!("{:0width$X}", v, width = mem.hex_digits())).collect();
+            row.join(", ")
+        }).collect();
+        writeln!(buf, "{};", rows.join(",\n"))?;
+
+        Ok(())
+    }
+}
+
+impl Format for Mif {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let mem = Memory::new(spwm, args);
+
+        header(buf, "--", spwm, args, &mem)?;
+        writeln!(buf, "WIDTH={};", mem.width)?;
+        writeln!(buf, "DEPTH={};\n", mem.data.len())?;
+        writeln!(buf, "ADDRESS_RADIX=UNS;")?;
+        writeln!(buf, "DATA_RADIX=HEX;\n")?;
+        writeln!(buf, "CONTENT BEGIN")?;
+        for (idx, v) in mem.data.iter().enumerate() {
+            writeln!(buf, "    {} : {:0width$X};", idx, v, width = mem.hex_digits())?;
+        }
+        writeln!(buf, "END;")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::io::{Result, Write, Error, ErrorKind};
+
+use crate::SPWM;
+
+use super::{Format, UserSection, FormatArgs};
+
+const RECORD_DATA: u8               = 0x00;
+const RECORD_EOF: u8                = 0x01;
+const RECORD_EXTENDED_LINEAR: u8    = 0x04;
+
+/// Intel HEX file, inverter table is placed right after the table
+#[derive(Default)]
+pub struct IntelHex;
+
+impl Format for IntelHex {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let bytes = args.pack_tables(spwm)?;
+
+        let record_len = args.record_len;
+        if !(1..=255).contains(&record_len) {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("record length {} of Intel HEX must be 1 to 255", record_len)));
+        }
+        if args.address as u64 + bytes.len() as u64 > 1 << 32 {
+            return Err(Error::new(ErrorKind::InvalidInput, "table does not fit in 32 bit address space of Intel HEX"));
+        }
+        let mut upper = None;
+        let mut offset = 0;
+
+        while offset < bytes.len() {
+            let address = args.address + offset as u32;
+            if upper != Some(address >> 16) {
+                upper = Some(address >> 16);
+                write_record(buf, 0, RECORD_EXTENDED_LINEAR, &((address >> 16) as u16).to_be_bytes())?;
+            }
+            // record must not cross 64K segment
+            let left = 0x10000 - (address & 0xFFFF) as usize;
+            let len = record_len.min(left).min(bytes.len() - offset);
+            write_record(buf, address as u16, RECORD_DATA, &bytes[offset..offset + len])?;
+            offset += len;
+        }
+        write_record(buf, 0, RECORD_EOF, &[])?;
+
+        Ok(())
+    }
+}
+
+fn write_record(buf: &mut dyn Write, address: u16, ty: u8, data: &[u8]) -> Result<()> {
+    let mut sum = data.len() as u8;
+    sum = sum.wrapping_add((address >> 8) as u8).wrapping_add(address as u8).wrapping_add(ty);
+    write!(buf, ":{:02X}{:04X}{:02X}", data.len(), address, ty)?;
+    for b in data {
+        sum = sum.wrapping_add(*b);
+        write!(buf, "{:02X}", b)?;
+    }
+    writeln!(buf, "{:02X}", sum.wrapping_neg())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(address: u16, ty: u8, data: &[u8]) -> String {
+        let mut buf = Vec::new();
+        write_record(&mut buf, address, ty, data).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    /// (address, type, data) of each record, checksum of every record is verified
+    fn records(text: &str) -> Vec<(u16, u8, Vec<u8>)> {
+        text.lines().map(|line| {
+            let bytes: Vec<u8> = (1..line.len()).step_by(2)
+                .map(|idx| u8::from_str_radix(&line[idx..idx + 2], 16).unwrap())
+                .collect();
+            assert_eq!(bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)), 0, "{}", line);
+            assert_eq!(bytes[0] as usize, bytes.len() - 5);
+            (u16::from_be_bytes([bytes[1], bytes[2]]), bytes[3], bytes[4..bytes.len() - 1].to_vec())
+        }).collect()
+    }
+
+    #[test]
+    fn known_records() {
+        let data = [0x21, 0x46, 0x01, 0x36, 0x01, 0x21, 0x47, 0x01, 0x36, 0x00, 0x7E, 0xFE, 0x09, 0xD2, 0x19, 0x01];
+        assert_eq!(record(0x0100, RECORD_DATA, &data), ":10010000214601360121470136007EFE09D2190140\n");
+        assert_eq!(record(0, RECORD_EXTENDED_LINEAR, &[0x08, 0x00]), ":020000040800F2\n");
+        assert_eq!(record(0, RECORD_EOF, &[]), ":00000001FF\n");
+    }
+
+    #[test]
+    fn split_records_at_segment() {
+        let spwm = SPWM::new(50.0, 16, 0.00125, 255, 0);
+        let table = spwm.lookup_table();
+        let args = FormatArgs {
+            address: 0x0800_FFF8,
+            ..Default::default()
+        };
+
+        let mut buf = Vec::new();
+        IntelHex.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+        let records = records(&String::from_utf8(buf).unwrap());
+
+        assert_eq!(records, vec![
+            (0, RECORD_EXTENDED_LINEAR, vec![0x08, 0x00]),
+            (0xFFF8, RECORD_DATA, table[..8].iter().map(|v| *v as u8).collect()),
+            (0, RECORD_EXTENDED_LINEAR, vec![0x08, 0x01]),
+            (0x0000, RECORD_DATA, table[8..].iter().map(|v| *v as u8).collect()),
+            (0, RECORD_EOF, vec![]),
+        ]);
+    }
+
+    #[test]
+    fn reject_out_of_range() {
+        let spwm = SPWM::new(50.0, 16, 0.00125, 255, 0);
+        for (record_len, address) in [(0, 0), (256, 0), (16, 0xFFFF_FFF8)] {
+            let args = FormatArgs {
+                record_len,
+                address,
+                ..Default::default()
+            };
+            let err = IntelHex.write(&spwm, &mut Vec::new(), &UserSection::new(""), &args).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+
+        // last byte at top of address space
+        let args = FormatArgs {
+            record_len: 255,
+            address: 0xFFFF_FFF0,
+            ..Default::default()
+        };
+        let mut buf = Vec::new();
+        IntelHex.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+        let records = records(&String::from_utf8(buf).unwrap());
+        assert_eq!(records[1].0, 0xFFF0);
+        assert_eq!(records[1].2.len(), 16);
+    }
+
+    #[test]
+    fn split_records_by_length() {
+        let spwm = SPWM::new(50.0, 16, 0.00125, 1000, 0);
+        let args = FormatArgs {
+            record_len: 10,
+            inverter: Some(0.0),
+            ..Default::default()
+        };
+
+        let mut buf = Vec::new();
+        IntelHex.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+        let records = records(&String::from_utf8(buf).unwrap());
+
+        // 16 samples of 2 bytes for table and inverter table
+        let data: Vec<_> = records.iter().filter(|r| r.1 == RECORD_DATA).collect();
+        assert_eq!(data.iter().map(|r| r.2.len()).collect::<Vec<_>>(), vec![10, 10, 10, 10, 10, 10, 4]);
+        assert_eq!(data.iter().map(|r| r.0).collect::<Vec<_>>(), vec![0, 10, 20, 30, 40, 50, 60]);
+        assert_eq!(data.iter().flat_map(|r| r.2.clone()).collect::<Vec<_>>(), args.pack_tables(&spwm).unwrap());
+    }
+}
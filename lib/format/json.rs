@@ -0,0 +1,95 @@
+use std::io::{Result, Write, BufWriter};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs};
+
+/// JSON document holding generator parameters and tables:
+///
+/// ```json
+/// {
+///   "parameters": { "name": "WAVE", "sin_freq": 50, ... },
+///   "table": [ ... ],
+///   "table_not": [ ... ]
+/// }
+/// ```
+///
+/// `table_not` is written only when inverter is set
+#[derive(Default)]
+pub struct Json;
+
+impl Format for Json {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let table_not = args.inverter.map(|pad| spwm.table_not(&table, pad));
+
+        let mut buf = BufWriter::new(buf);
+        writeln!(buf, "{{")?;
+        writeln!(buf, "  \"parameters\": {{")?;
+        writeln!(buf, "    \"name\": \"{}\",", escape(&args.name))?;
+        writeln!(buf, "    \"sin_freq\": {},", number(spwm.sin_freq()))?;
+        writeln!(buf, "    \"carrier_freq\": {},", number(spwm.carrier_freq()))?;
+        writeln!(buf, "    \"step\": {},", number(spwm.step()))?;
+        writeln!(buf, "    \"num_of_samples\": {},", spwm.num_of_samples())?;
+        writeln!(buf, "    \"pwm_top\": {},", spwm.pwm_top())?;
+        writeln!(buf, "    \"padding\": {},", spwm.padding())?;
+        match args.inverter {
+            Some(pad) => writeln!(buf, "    \"inverter\": {}", number(pad))?,
+            None => writeln!(buf, "    \"inverter\": null")?,
+        }
+        writeln!(buf, "  }},")?;
+        write_array(&mut buf, "table", &table, args.row_width()?, table_not.is_none())?;
+        if let Some(table_not) = &table_not {
+            write_array(&mut buf, "table_not", table_not, args.row_width()?, true)?;
+        }
+        writeln!(buf, "}}")?;
+        buf.flush()?;
+
+        Ok(())
+    }
+}
+
+fn write_array(buf: &mut dyn Write, key: &str, table: &[DutyCycle], width: usize, last: bool) -> Result<()> {
+    writeln!(buf, "  \"{}\": [", key)?;
+    let rows = table.chunks(width).count();
+    for (idx, row) in table.chunks(width).enumerate() {
+        let row = row.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+        if idx + 1 < rows {
+            writeln!(buf, "    {},", row)?;
+        }
+        else {
+            writeln!(buf, "    {}", row)?;
+        }
+    }
+    writeln!(buf, "  ]{}", if last { "" } else { "," })
+}
+
+/// JSON has no representation of NaN and infinity
+fn number(val: f64) -> String {
+    if val.is_finite() {
+        val.to_string()
+    }
+    else {
+        "null".to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
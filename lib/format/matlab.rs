@@ -0,0 +1,206 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs, Endian, WordWidth};
+
+/// MATLAB script assigning `table`, `table_not`, `carrier_freq`, `sin_freq` and `pwm_top` variables
+#[derive(Default)]
+pub struct MatlabScript;
+
+impl Format for MatlabScript {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        Some("%")
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let ty = class_name(args.word_width(spwm)?);
+
+        writeln!(buf, "% {} {}Hz, carrier {}Hz, pwm top {}\n", args.name, spwm.sin_freq(), spwm.carrier_freq(), spwm.pwm_top())?;
+        sections.write(0, buf)?;
+        writeln!(buf, "carrier_freq = {};", spwm.carrier_freq())?;
+        writeln!(buf, "sin_freq = {};", spwm.sin_freq())?;
+        writeln!(buf, "pwm_top = {};\n", spwm.pwm_top())?;
+        sections.write(1, buf)?;
+        write_vector(buf, "table", ty, &table, args.row_width()?)?;
+        sections.write(2, buf)?;
+        if let Some(pad) = args.inverter {
+            write_vector(buf, "table_not", ty, &spwm.table_not(&table, pad), args.row_width()?)?;
+        }
+        sections.write(3, buf)?;
+        sections.write_remains(4, buf)?;
+
+        Ok(())
+    }
+}
+
+fn write_vector(buf: &mut dyn Write, name: &str, ty: &str, table: &[DutyCycle], width: usize) -> Result<()> {
+    writeln!(buf, "{} = {}([ ...", name, ty)?;
+    for row in table.chunks(width) {
+        write!(buf, "   ")?;
+        for val in row {
+            write!(buf, " {}", val)?;
+        }
+        writeln!(buf, " ...")?;
+    }
+    writeln!(buf, "]);\n")
+}
+
+fn class_name(width: WordWidth) -> &'static str {
+    match width {
+        WordWidth::U8 => "uint8",
+        WordWidth::U16 => "uint16",
+        WordWidth::U32 => "uint32",
+    }
+}
+
+// data types and array classes of MAT-file level 5
+const MI_INT8: u32          = 1;
+const MI_INT32: u32         = 5;
+const MI_UINT32: u32        = 6;
+const MI_DOUBLE: u32        = 9;
+const MI_MATRIX: u32        = 14;
+const MX_DOUBLE_CLASS: u32  = 6;
+
+/// MATLAB level 5 `.mat` file with `table`, `table_not`, `carrier_freq` and `sin_freq` variables,
+/// tables are row vectors of unsigned class of word width
+#[derive(Default)]
+pub struct MatFile;
+
+impl Format for MatFile {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let mat = Mat { endian: args.endian };
+
+        let mut text = format!("MATLAB 5.0 MAT-file, Platform: {}, Created by: spwm_generator, {} {}Hz",
+            std::env::consts::OS, args.name, spwm.sin_freq()).into_bytes();
+        text.resize(116, b' ');
+
+        buf.write_all(&text)?;
+        buf.write_all(&[0; 8])?;
+        buf.write_all(&mat.endian.u16_bytes(0x0100))?;
+        buf.write_all(&mat.endian.u16_bytes(u16::from_be_bytes(*b"MI")))?;
+
+        buf.write_all(&mat.table(spwm, args, "table", &table)?)?;
+        if let Some(pad) = args.inverter {
+            buf.write_all(&mat.table(spwm, args, "table_not", &spwm.table_not(&table, pad))?)?;
+        }
+        buf.write_all(&mat.scalar("carrier_freq", spwm.carrier_freq()))?;
+        buf.write_all(&mat.scalar("sin_freq", spwm.sin_freq()))?;
+
+        Ok(())
+    }
+}
+
+struct Mat {
+    endian:     Endian,
+}
+
+impl Mat {
+    /// tagged data element padded to 8 bytes
+    fn element(&self, ty: u32, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + data.len().next_multiple_of(8));
+        bytes.extend_from_slice(&self.endian.u32_bytes(ty));
+        bytes.extend_from_slice(&self.endian.u32_bytes(data.len() as u32));
+        bytes.extend_from_slice(data);
+        bytes.resize(8 + data.len().next_multiple_of(8), 0);
+        bytes
+    }
+
+    fn matrix(&self, name: &str, class: u32, cols: usize, ty: u32, data: &[u8]) -> Vec<u8> {
+        let mut flags = self.endian.u32_bytes(class).to_vec();
+        flags.extend_from_slice(&[0; 4]);
+        let mut dims = self.endian.u32_bytes(1).to_vec();
+        dims.extend_from_slice(&self.endian.u32_bytes(cols as u32));
+
+        let mut body = self.element(MI_UINT32, &flags);
+        body.extend(self.element(MI_INT32, &dims));
+        body.extend(self.element(MI_INT8, name.as_bytes()));
+        body.extend(self.element(ty, data));
+        self.element(MI_MATRIX, &body)
+    }
+
+    fn table(&self, spwm: &SPWM, args: &FormatArgs, name: &str, table: &[DutyCycle]) -> Result<Vec<u8>> {
+        // mxUINT8/16/32 classes and miUINT8/16/32 types
+        let (class, ty) = match args.word_width(spwm)? {
+            WordWidth::U8 => (9, 2),
+            WordWidth::U16 => (11, 4),
+            WordWidth::U32 => (13, 6),
+        };
+        Ok(self.matrix(name, class, table.len(), ty, &args.pack(spwm, table)?))
+    }
+
+    fn scalar(&self, name: &str, val: f64) -> Vec<u8> {
+        self.matrix(name, MX_DOUBLE_CLASS, 1, MI_DOUBLE, &self.endian.f64_bytes(val))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_sections_without_inverter() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        let args = FormatArgs::default();
+
+        let mut sections = UserSection::new("%");
+        for index in 0..4 {
+            sections.sections.insert(index, format!("user {}\n", index));
+        }
+        let mut buf = Vec::new();
+        MatlabScript.write(&spwm, &mut buf, &sections, &args).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+
+        assert_eq!(UserSection::parse(&text, "%").sections, sections.sections);
+        assert_eq!(text.matches("% USER SECTION BEGIN 3").count(), 1);
+    }
+
+    #[test]
+    fn element_padding() {
+        let mat = Mat { endian: Endian::Little };
+        assert_eq!(mat.element(MI_INT8, b"table"), b"\x01\0\0\0\x05\0\0\0table\0\0\0");
+        assert_eq!(mat.element(MI_INT8, b"sin_freq"), b"\x01\0\0\0\x08\0\0\0sin_freq");
+        assert_eq!(mat.element(MI_INT8, b""), b"\x01\0\0\0\0\0\0\0");
+
+        let mat = Mat { endian: Endian::Big };
+        assert_eq!(mat.element(MI_INT32, &[1, 2, 3]), b"\0\0\0\x05\0\0\0\x03\x01\x02\x03\0\0\0\0\0");
+    }
+
+    #[test]
+    fn file_layout() {
+        let spwm = SPWM::new(50.0, 5, 0.004, 255, 0);
+        let args = FormatArgs {
+            inverter: Some(0.0),
+            ..Default::default()
+        };
+
+        let mut buf = Vec::new();
+        MatFile.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+
+        assert!(buf.starts_with(b"MATLAB 5.0 MAT-file"));
+        assert_eq!(&buf[124..128], b"\x00\x01IM");
+        // every top level element is padded to 8 bytes
+        let mut offset = 128;
+        let mut names = Vec::new();
+        while offset < buf.len() {
+            let ty = u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+            let len = u32::from_le_bytes(buf[offset + 4..offset + 8].try_into().unwrap()) as usize;
+            assert_eq!(ty, MI_MATRIX);
+            assert_eq!(len % 8, 0);
+            // flags and dims elements take 32 bytes, followed by name element
+            let name = offset + 8 + 32;
+            let name_len = u32::from_le_bytes(buf[name + 4..name + 8].try_into().unwrap()) as usize;
+            names.push(String::from_utf8(buf[name + 8..name + 8 + name_len].to_vec()).unwrap());
+            offset += 8 + len;
+        }
+        assert_eq!(offset, buf.len());
+        assert_eq!(names, vec!["table", "table_not", "carrier_freq", "sin_freq"]);
+    }
+}
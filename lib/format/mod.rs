@@ -0,0 +1,470 @@
+use std::{io::{Read, Write, Error, ErrorKind}, fs, collections::HashMap, path::Path};
+
+use crate::{SPWM, DutyCycle, Alignment};
+
+
+
+mod raw;
+mod raw_hex;
+mod c;
+mod rust;
+mod intel_hex;
+mod binary;
+mod srec;
+mod fpga;
+mod vhdl;
+mod verilog;
+mod csv;
+mod json;
+mod numpy;
+mod matlab;
+mod spice;
+mod vcd;
+mod naming;
+mod cpp;
+mod arduino;
+mod asm;
+mod template;
+
+pub use raw::*;
+pub use raw_hex::*;
+pub use c::*;
+pub use rust::*;
+pub use intel_hex::*;
+pub use binary::*;
+pub use srec::*;
+pub use fpga::*;
+pub use vhdl::*;
+pub use verilog::*;
+pub use csv::*;
+pub use json::*;
+pub use numpy::*;
+pub use matlab::*;
+pub use spice::*;
+pub use vcd::*;
+pub use naming::*;
+pub use cpp::*;
+pub use arduino::*;
+pub use asm::*;
+pub use template::*;
+
+#[derive(Debug, Clone)]
+pub struct UserSection {
+    pub sections:           HashMap<i32, String>,
+    /// line comment marker of target language
+    pub comment:            String,
+}
+
+const USER_SECTION_BEGIN: &str    = "USER SECTION BEGIN";
+const USER_SECTION_END: &str      = "USER SECTION END";
+
+enum SectionState {
+    None,
+    Section,
+}
+
+/// Rendering options of formats, start from [`FormatArgs::default`] and set fields,
+/// new options may be added in minor versions
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FormatArgs {
+    pub name:           String,
+    pub separator:      String,
+    pub width:          usize,
+    pub inverter:       Option<f64>,
+    /// word width of binary formats, derived from pwm top when not provided
+    pub word_width:     Option<WordWidth>,
+    pub endian:         Endian,
+    /// start address of binary formats
+    pub address:        u32,
+    /// number of data bytes in each record of hex formats
+    pub record_len:     usize,
+    /// write header before binary table
+    pub header:         bool,
+    /// pad memory depth to power of two
+    pub pad_pow2:       bool,
+    pub rom_style:      RomStyle,
+    /// pulse placement of gate signal formats
+    pub align:          Alignment,
+    /// gate signal high level in volt
+    pub high:           f64,
+    /// gate signal low level in volt
+    pub low:            f64,
+    /// gate signal rise time in seconds
+    pub rise:           f64,
+    /// gate signal fall time in seconds
+    pub fall:           f64,
+    pub timescale:      Timescale,
+    pub rust_item:      RustItem,
+    /// link section attribute of Rust tables
+    pub link_section:   Option<String>,
+    /// template of table identifiers
+    pub name_template:  NameTemplate,
+    /// casing of table identifiers, language default when not provided
+    pub name_case:      Option<NameCase>,
+    /// header file name included by split C source and Arduino sketch,
+    /// C source is split from its header when provided
+    pub include:        Option<String>,
+    /// namespace of C++ format, nested with `::`
+    pub namespace:      String,
+    /// write accessor functions of tables
+    pub accessor:       bool,
+    /// CPU clock of Arduino sketch in Hz
+    pub f_cpu:          u32,
+    /// section of assembly formats, dialect default when not provided
+    pub asm_section:    Option<String>,
+    /// line comment marker of user sections in template output
+    pub template_comment: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endian {
+    pub fn u16_bytes(&self, val: u16) -> [u8; 2] {
+        match self {
+            Endian::Little => val.to_le_bytes(),
+            Endian::Big => val.to_be_bytes(),
+        }
+    }
+
+    pub fn u32_bytes(&self, val: u32) -> [u8; 4] {
+        match self {
+            Endian::Little => val.to_le_bytes(),
+            Endian::Big => val.to_be_bytes(),
+        }
+    }
+
+    pub fn f64_bytes(&self, val: f64) -> [u8; 8] {
+        match self {
+            Endian::Little => val.to_le_bytes(),
+            Endian::Big => val.to_be_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WordWidth {
+    U8,
+    U16,
+    U32,
+}
+
+impl WordWidth {
+    /// smallest word that holds pwm top
+    pub fn from_top(pwm_top: DutyCycle) -> Self {
+        if pwm_top >= 65536 {
+            WordWidth::U32
+        }
+        else if pwm_top >= 256 {
+            WordWidth::U16
+        }
+        else {
+            WordWidth::U8
+        }
+    }
+
+    /// return size of word in bytes
+    pub fn bytes(&self) -> usize {
+        match self {
+            WordWidth::U8 => 1,
+            WordWidth::U16 => 2,
+            WordWidth::U32 => 4,
+        }
+    }
+}
+
+impl Default for FormatArgs {
+    /// same defaults as command line options
+    fn default() -> Self {
+        Self {
+            name: "WAVE".to_string(),
+            separator: ", ".to_string(),
+            width: 16,
+            inverter: None,
+            word_width: None,
+            endian: Endian::default(),
+            address: 0,
+            record_len: 16,
+            header: false,
+            pad_pow2: false,
+            rom_style: RomStyle::default(),
+            align: Alignment::default(),
+            high: 1.0,
+            low: 0.0,
+            rise: 1e-8,
+            fall: 1e-8,
+            timescale: Timescale::default(),
+            rust_item: RustItem::default(),
+            link_section: None,
+            name_template: NameTemplate::default(),
+            name_case: None,
+            include: None,
+            namespace: "spwm".to_string(),
+            accessor: false,
+            f_cpu: 16_000_000,
+            asm_section: None,
+            template_comment: "//".to_string(),
+        }
+    }
+}
+
+impl FormatArgs {
+    /// return number of samples in each row of text formats, error when zero
+    pub fn row_width(&self) -> std::io::Result<usize> {
+        if self.width == 0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "row width must be at least 1"));
+        }
+        Ok(self.width)
+    }
+
+    /// return word width of binary formats, error when requested width can not hold pwm top
+    pub fn word_width(&self, spwm: &SPWM) -> std::io::Result<WordWidth> {
+        let min = WordWidth::from_top(spwm.pwm_top());
+        match self.word_width {
+            Some(width) if width < min => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("word width of {} bytes can not hold pwm top {}", width.bytes(), spwm.pwm_top())
+            )),
+            Some(width) => Ok(width),
+            None => Ok(min),
+        }
+    }
+
+    /// pack table as words
+    pub fn pack(&self, spwm: &SPWM, table: &[DutyCycle]) -> std::io::Result<Vec<u8>> {
+        let width = self.word_width(spwm)?;
+        let mut bytes = Vec::with_capacity(table.len() * width.bytes());
+        for val in table {
+            let val = *val as u32;
+            match (width, self.endian) {
+                (WordWidth::U8, _) => bytes.push(val as u8),
+                (WordWidth::U16, Endian::Little) => bytes.extend_from_slice(&(val as u16).to_le_bytes()),
+                (WordWidth::U16, Endian::Big) => bytes.extend_from_slice(&(val as u16).to_be_bytes()),
+                (WordWidth::U32, Endian::Little) => bytes.extend_from_slice(&val.to_le_bytes()),
+                (WordWidth::U32, Endian::Big) => bytes.extend_from_slice(&val.to_be_bytes()),
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// pack table followed by inverter table when inverter mode is enabled
+    pub fn pack_tables(&self, spwm: &SPWM) -> std::io::Result<Vec<u8>> {
+        let table = spwm.lookup_table();
+        let mut bytes = self.pack(spwm, &table)?;
+        if let Some(pad) = self.inverter {
+            bytes.extend(self.pack(spwm, &spwm.table_not(&table, pad))?);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Output format of lookup table
+///
+/// ```rust
+/// use spwm_generator::SPWM;
+/// use spwm_generator::format::*;
+///
+/// let spwm = SPWM::new(50.0, 200, 0.0001, 255, 0);
+/// let args = FormatArgs::default();
+/// let sections = UserSection::new(CFile.comment(&args).unwrap_or_default());
+///
+/// let mut buf = Vec::new();
+/// CFile.write(&spwm, &mut buf, &sections, &args).unwrap();
+///
+/// let text = String::from_utf8(buf).unwrap();
+/// assert!(text.contains("const uint8_t WAVE_50HZ[WAVE_50HZ_LEN]"));
+/// ```
+pub trait Format {
+    /// line comment marker of user sections, `None` when format has no user sections
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        Some("//")
+    }
+
+    /// render output into `buf`, user sections are written back from `sections`
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> std::io::Result<()>;
+
+    /// write output to file, user sections of existing file are kept
+    fn write_file(&self, spwm: &SPWM, path: &Path, args: &FormatArgs) -> std::io::Result<()> {
+        write_merged(path, self.comment(args), |sections, buf| self.write(spwm, buf, sections, args))
+    }
+}
+
+/// render into file at `path`, user sections of existing file are passed to `render` when `comment` is provided.
+/// file is replaced only after rendering succeed
+pub fn write_merged(
+    path: &Path,
+    comment: Option<&str>,
+    render: impl FnOnce(&UserSection, &mut dyn Write) -> std::io::Result<()>
+) -> std::io::Result<()> {
+    let sections = match comment {
+        Some(comment) => match fs::File::open(path) {
+            Ok(mut file) => UserSection::read_user_reign_with(&mut file, comment)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => UserSection::new(comment),
+            Err(err) => return Err(err),
+        },
+        None => UserSection::new(""),
+    };
+
+    let mut buf = Vec::new();
+    render(&sections, &mut buf)?;
+    fs::write(path, buf)
+}
+
+impl UserSection {
+    /// empty user sections marked with given line comment
+    pub fn new(comment: &str) -> Self {
+        UserSection {
+            sections: HashMap::new(),
+            comment: comment.to_string(),
+        }
+    }
+
+    pub fn write(&self, index: i32, fs: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(fs, "{} {} {}", self.comment, USER_SECTION_BEGIN, index)?;
+        if let Some(txt) = self.sections.get(&index) {
+            write!(fs, "{}", txt)?;
+        }
+        writeln!(fs, "{} {} {}\n", self.comment, USER_SECTION_END, index)
+    }
+
+    pub fn write_remains(&self, offset: i32, fs: &mut dyn Write) -> std::io::Result<()> {
+        if (offset as usize) < self.sections.len() {
+            for section in self.sections.iter() {
+                if *section.0 >= offset {
+                    self.write(*section.0, fs)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read_user_reign(fs: &mut impl Read) -> std::io::Result<UserSection> {
+        Self::read_user_reign_with(fs, "//")
+    }
+
+    /// read user sections marked with given line comment
+    pub fn read_user_reign_with(fs: &mut impl Read, comment: &str) -> std::io::Result<UserSection> {
+        let mut buf = String::new();
+        fs.read_to_string(&mut buf)?;
+        Ok(Self::parse(&buf, comment))
+    }
+
+    /// parse user sections marked with given line comment from text
+    pub fn parse(buf: &str, comment: &str) -> UserSection {
+        let begin = format!("{} {}", comment, USER_SECTION_BEGIN);
+        let end = format!("{} {}", comment, USER_SECTION_END);
+
+        let mut state = SectionState::None;
+        let mut user = UserSection::new(comment);
+
+        let mut temp = (-1, String::new());
+
+        for mut line in buf.lines() {
+            match state {
+                SectionState::None => {
+                    line = line.trim();
+
+                    // markers without a section number are not user sections
+                    if let Some(Ok(index)) = line.strip_prefix(begin.as_str()).map(|x| x.trim().parse()) {
+                        temp.0 = index;
+                        state = SectionState::Section;
+                    }
+                },
+                SectionState::Section => {
+                    let temp_line = line.trim();
+                    if temp_line.strip_prefix(end.as_str()).and_then(|x| x.trim().parse().ok()) == Some(temp.0) {
+                        state = SectionState::None;
+                        user.sections.insert(temp.0, temp.1);
+                        temp = (-1, String::new());
+                    }
+                    else {
+                        temp.1.push_str(line);
+                        temp.1.push('\n');
+                    }
+                },
+            }
+        }
+
+        user
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_skip_malformed_markers() {
+        let text = "\
+// USER SECTION BEGIN x
+ignored
+// USER SECTION END x
+// USER SECTION BEGIN 1
+kept
+// USER SECTION END
+// USER SECTION END one
+// USER SECTION END 1
+// USER SECTION BEGIN
+";
+        let sections = UserSection::parse(text, "//");
+        assert_eq!(sections.sections.len(), 1);
+        assert_eq!(sections.sections[&1], "kept\n// USER SECTION END\n// USER SECTION END one\n");
+    }
+
+    #[test]
+    fn pack_reject_narrow_word_width() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 1000, 0);
+        let args = FormatArgs {
+            word_width: Some(WordWidth::U8),
+            ..Default::default()
+        };
+
+        let err = args.pack_tables(&spwm).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        for format in [&IntelHex as &dyn Format, &Binary, &SRecord, &Npy, &MatFile, &AvrAsm] {
+            let err = format.write(&spwm, &mut Vec::new(), &UserSection::new(""), &args).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn reject_zero_row_width() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        let args = FormatArgs {
+            width: 0,
+            inverter: Some(0.0001),
+            ..Default::default()
+        };
+
+        assert_eq!(args.row_width().unwrap_err().kind(), ErrorKind::InvalidInput);
+        let formats: [&dyn Format; 16] = [
+            &Raw, &RawHex, &CFile, &CHexFile, &RustFile, &RustHexFile, &ReadMemH, &Coe,
+            &VhdlFile, &Json, &MatlabScript, &SpicePwl, &CppFile, &ArduinoFile, &GnuAsm, &AvrAsm,
+        ];
+        for format in formats {
+            let err = format.write(&spwm, &mut Vec::new(), &UserSection::new("//"), &args).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn pack_wider_word_width() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        let args = FormatArgs {
+            word_width: Some(WordWidth::U16),
+            endian: Endian::Big,
+            ..Default::default()
+        };
+
+        let table = spwm.lookup_table();
+        let bytes = args.pack(&spwm, &table).unwrap();
+        assert_eq!(bytes.len(), table.len() * 2);
+        assert_eq!(bytes[..2], (table[0] as u16).to_be_bytes());
+    }
+}
@@ -0,0 +1,191 @@
+use std::str::FromStr;
+
+use crate::SPWM;
+
+use super::FormatArgs;
+
+/// Identifier rules of target language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    C,
+    Cpp,
+    Rust,
+    Vhdl,
+    Verilog,
+}
+
+/// Casing applied to rendered names before sanitising
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameCase {
+    Keep,
+    Upper,
+    Lower,
+}
+
+const PLACEHOLDERS: [&str; 6] = ["name", "freq", "carrier", "carrier_k", "samples", "top"];
+
+/// Name template with placeholders in braces, e.g. `{name}_{freq}HZ_{carrier_k}K`
+///
+/// | placeholder   | value                          |
+/// |---------------|--------------------------------|
+/// | `{name}`      | `--name` argument              |
+/// | `{freq}`      | sin frequency in Hz            |
+/// | `{carrier}`   | carrier frequency in Hz        |
+/// | `{carrier_k}` | carrier frequency in kHz       |
+/// | `{samples}`   | number of samples              |
+/// | `{top}`       | pwm top                        |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameTemplate(String);
+
+impl Default for NameTemplate {
+    fn default() -> Self {
+        Self("{name}_{freq}HZ".to_string())
+    }
+}
+
+impl FromStr for NameTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s;
+        while let Some(pos) = rest.find(['{', '}']) {
+            if rest[pos..].starts_with('}') {
+                return Err(format!("unexpected '}}' in name template \"{}\"", s));
+            }
+            let end = rest[pos..].find('}').ok_or_else(|| format!("unclosed '{{' in name template \"{}\"", s))?;
+            let key = &rest[pos + 1..pos + end];
+            if !PLACEHOLDERS.contains(&key) {
+                return Err(format!("unknown placeholder {{{}}}, expected one of {}", key,
+                    PLACEHOLDERS.map(|x| format!("{{{}}}", x)).join(", ")));
+            }
+            rest = &rest[pos + end + 1..];
+        }
+        if s.trim().is_empty() {
+            return Err("name template is empty".to_string());
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl NameTemplate {
+    /// replace placeholders with values of spwm
+    pub fn render(&self, name: &str, spwm: &SPWM) -> String {
+        self.0
+            .replace("{name}", name)
+            .replace("{freq}", &spwm.sin_freq().to_string())
+            .replace("{carrier_k}", &(spwm.carrier_freq() / 1000.0).to_string())
+            .replace("{carrier}", &spwm.carrier_freq().to_string())
+            .replace("{samples}", &spwm.num_of_samples().to_string())
+            .replace("{top}", &spwm.pwm_top().to_string())
+    }
+}
+
+impl FormatArgs {
+    /// return identifier of tables rendered from name template, valid in target language
+    pub fn ident(&self, spwm: &SPWM, lang: Language) -> String {
+        let name = self.name_template.render(&self.name, spwm);
+        let case = self.name_case.unwrap_or(match lang {
+            Language::Rust => NameCase::Upper,
+            _ => NameCase::Keep,
+        });
+        let name = match case {
+            NameCase::Keep => name,
+            NameCase::Upper => name.to_uppercase(),
+            NameCase::Lower => name.to_lowercase(),
+        };
+        sanitize(&name, lang)
+    }
+}
+
+/// replace invalid characters with `_` and avoid leading digit and keywords of target language
+pub fn sanitize(text: &str, lang: Language) -> String {
+    let mut out: String = text.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+
+    match lang {
+        Language::Vhdl => {
+            // basic identifier start with letter, without double or trailing underscores
+            let mut collapsed = String::with_capacity(out.len());
+            for c in out.chars() {
+                if !(c == '_' && collapsed.ends_with('_')) {
+                    collapsed.push(c);
+                }
+            }
+            out = collapsed.trim_end_matches('_').to_string();
+            if !out.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                out.insert_str(0, if out.starts_with('_') { "X" } else { "X_" });
+            }
+            if VHDL_KEYWORDS.contains(&out.to_lowercase().as_str()) {
+                out.push_str("_X");
+            }
+        },
+        _ => {
+            if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+                out.insert(0, '_');
+            }
+            let keywords: &[&str] = match lang {
+                Language::C => &C_KEYWORDS,
+                Language::Cpp => &CPP_KEYWORDS,
+                Language::Rust => &RUST_KEYWORDS,
+                _ => &VERILOG_KEYWORDS,
+            };
+            let reserved = keywords.contains(&out.as_str()) || (lang == Language::Cpp && C_KEYWORDS.contains(&out.as_str()));
+            if out == "_" || reserved {
+                out.push('_');
+            }
+        },
+    }
+    out
+}
+
+const C_KEYWORDS: [&str; 44] = [
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum",
+    "extern", "float", "for", "goto", "if", "inline", "int", "long", "register", "restrict", "return",
+    "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void",
+    "volatile", "while", "_Alignas", "_Alignof", "_Atomic", "_Bool", "_Complex", "_Generic",
+    "_Imaginary", "_Noreturn", "_Static_assert", "_Thread_local",
+];
+
+/// keywords of C++ that are not C keywords
+const CPP_KEYWORDS: [&str; 59] = [
+    "alignas", "alignof", "and", "and_eq", "asm", "bitand", "bitor", "bool", "catch", "char8_t",
+    "char16_t", "char32_t", "class", "compl", "concept", "consteval", "constexpr", "constinit",
+    "const_cast", "co_await", "co_return", "co_yield", "decltype", "delete", "dynamic_cast",
+    "explicit", "export", "false", "friend", "mutable", "namespace", "new", "noexcept", "not",
+    "not_eq", "nullptr", "operator", "or", "or_eq", "private", "protected", "public",
+    "reinterpret_cast", "requires", "static_assert", "static_cast", "template", "this", "thread_local",
+    "throw", "true", "try", "typeid", "typename", "using", "virtual", "wchar_t", "xor", "xor_eq",
+];
+
+const RUST_KEYWORDS: [&str; 52] = [
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for", "if",
+    "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "async",
+    "await", "dyn", "abstract", "become", "box", "do", "final", "macro", "override", "priv", "typeof",
+    "unsized", "virtual", "yield", "try", "gen",
+];
+
+const VHDL_KEYWORDS: [&str; 97] = [
+    "abs", "access", "after", "alias", "all", "and", "architecture", "array", "assert", "attribute",
+    "begin", "block", "body", "buffer", "bus", "case", "component", "configuration", "constant",
+    "disconnect", "downto", "else", "elsif", "end", "entity", "exit", "file", "for", "function",
+    "generate", "generic", "group", "guarded", "if", "impure", "in", "inertial", "inout", "is", "label",
+    "library", "linkage", "literal", "loop", "map", "mod", "nand", "new", "next", "nor", "not", "null",
+    "of", "on", "open", "or", "others", "out", "package", "port", "postponed", "procedure", "process",
+    "pure", "range", "record", "register", "reject", "rem", "report", "return", "rol", "ror", "select",
+    "severity", "signal", "shared", "sla", "sll", "sra", "srl", "subtype", "then", "to", "transport",
+    "type", "unaffected", "units", "until", "use", "variable", "wait", "when", "while", "with", "xnor",
+    "xor",
+];
+
+const VERILOG_KEYWORDS: [&str; 72] = [
+    "always", "and", "assign", "automatic", "begin", "buf", "case", "casex", "casez", "deassign",
+    "default", "defparam", "disable", "edge", "else", "end", "endcase", "endfunction", "endgenerate",
+    "endmodule", "endtask", "event", "for", "force", "forever", "fork", "function", "generate",
+    "genvar", "if", "initial", "inout", "input", "integer", "join", "localparam", "module", "nand",
+    "negedge", "nor", "not", "or", "output", "parameter", "posedge", "real", "reg", "release", "repeat",
+    "signed", "supply0", "supply1", "task", "time", "tri", "unsigned", "wait", "wand", "while", "wire",
+    "wor", "xnor", "xor", "logic", "bit", "byte", "int", "shortint", "longint", "string", "package",
+    "interface",
+];
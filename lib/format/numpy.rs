@@ -0,0 +1,102 @@
+use std::io::{Result, Write};
+
+use crate::SPWM;
+
+use super::{Format, UserSection, FormatArgs, Endian, WordWidth};
+
+const MAGIC: &[u8; 6]       = b"\x93NUMPY";
+/// header length including magic is padded to multiple of this
+const HEADER_ALIGN: usize   = 64;
+
+/// NumPy `.npy` array (format version 1.0), dtype follows word width and endianness,
+/// shape is `(N,)` or `(2, N)` with inverter table as second row
+#[derive(Default)]
+pub struct Npy;
+
+impl Format for Npy {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let data = args.pack_tables(spwm)?;
+        let len = spwm.lookup_table().len();
+
+        let descr = match (args.word_width(spwm)?, args.endian) {
+            (WordWidth::U8, _) => "|u1",
+            (WordWidth::U16, Endian::Little) => "<u2",
+            (WordWidth::U16, Endian::Big) => ">u2",
+            (WordWidth::U32, Endian::Little) => "<u4",
+            (WordWidth::U32, Endian::Big) => ">u4",
+        };
+        let shape = if args.inverter.is_some() {
+            format!("(2, {})", len)
+        }
+        else {
+            format!("({},)", len)
+        };
+
+        let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+        // magic, version and header length take 10 bytes, header ends with new line
+        let total = MAGIC.len() + 4 + header.len() + 1;
+        header.push_str(&" ".repeat(total.next_multiple_of(HEADER_ALIGN) - total));
+        header.push('\n');
+
+        buf.write_all(MAGIC)?;
+        buf.write_all(&[1, 0])?;
+        buf.write_all(&(header.len() as u16).to_le_bytes())?;
+        buf.write_all(header.as_bytes())?;
+        buf.write_all(&data)?;
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn npy(spwm: &SPWM, args: &FormatArgs) -> (String, Vec<u8>) {
+        let mut buf = Vec::new();
+        Npy.write(spwm, &mut buf, &UserSection::new(""), args).unwrap();
+        assert_eq!(&buf[..8], b"\x93NUMPY\x01\x00");
+        let len = u16::from_le_bytes([buf[8], buf[9]]) as usize;
+        let header = String::from_utf8(buf[10..10 + len].to_vec()).unwrap();
+        (header, buf[10 + len..].to_vec())
+    }
+
+    #[test]
+    fn header_alignment() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 1000, 0);
+        for (inverter, endian, shape, descr) in [
+            (None, Endian::Little, "(8,)", "<u2"),
+            (Some(0.0), Endian::Big, "(2, 8)", ">u2"),
+        ] {
+            let args = FormatArgs {
+                inverter,
+                endian,
+                ..Default::default()
+            };
+            let (header, data) = npy(&spwm, &args);
+
+            assert_eq!((10 + header.len()) % HEADER_ALIGN, 0);
+            assert!(header.ends_with(" \n"));
+            assert_eq!(
+                header.trim_end(),
+                format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape)
+            );
+            assert_eq!(data, args.pack_tables(&spwm).unwrap());
+        }
+    }
+
+    #[test]
+    fn byte_dtype() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        let (header, data) = npy(&spwm, &FormatArgs::default());
+
+        assert!(header.contains("'descr': '|u1'"));
+        assert_eq!(header.len(), 118);
+        assert_eq!(data.len(), 8);
+    }
+}
@@ -1,6 +1,6 @@
-use std::{io::{BufWriter, Result, Write, Seek}, fs::File};
+use std::io::{Result, Write};
 
-use spwm_generator::SPWM;
+use crate::SPWM;
 
 use super::{Format, UserSection, FormatArgs};
 
@@ -10,19 +10,15 @@ use super::{Format, UserSection, FormatArgs};
 pub struct RawHex;
 
 impl Format for RawHex {
-    fn write(&self, spwm: &SPWM, buf: &mut File, args: &FormatArgs) -> Result<()> {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
         let table = spwm.lookup_table();
 
-        let sections = UserSection::read_user_reign(buf)?;
-        buf.rewind()?;
-        buf.set_len(0)?;
-
         sections.write(0, buf)?;
-        for row in table.chunks(args.width) {
+        for row in table.chunks(args.row_width()?) {
             for val in row {
                 write!(buf, "{:04X}{}", val, args.separator)?;
             }
-            writeln!(buf, "")?;
+            writeln!(buf)?;
         }
         sections.write(1, buf)?;
         sections.write_remains(2, buf)?;
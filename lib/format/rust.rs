@@ -0,0 +1,87 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs, Language, CInt, WordWidth};
+
+/// Item kind of generated Rust tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RustItem {
+    #[default]
+    Const,
+    /// single instance in memory, always used when link section is set
+    Static,
+}
+
+#[derive(Default)]
+pub struct RustFile;
+
+impl Format for RustFile {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let pad_width = CInt::from_top(spwm.pwm_top()).digits;
+
+        write_rust(spwm, buf, sections, args, |val| format!("{:width$}", val, width=pad_width))
+    }
+}
+
+#[derive(Default)]
+pub struct RustHexFile;
+
+impl Format for RustHexFile {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let pad_width = CInt::from_top(spwm.pwm_top()).hex_digits;
+
+        write_rust(spwm, buf, sections, args, |val| format!("0x{:0width$X}", val, width=pad_width))
+    }
+}
+
+fn write_rust(spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs, value: impl Fn(DutyCycle) -> String) -> Result<()> {
+    let table = spwm.lookup_table();
+    let name = args.ident(spwm, Language::Rust);
+
+    let ty = match WordWidth::from_top(spwm.pwm_top()) {
+        WordWidth::U8 => "u8",
+        WordWidth::U16 => "u16",
+        WordWidth::U32 => "u32",
+    };
+
+    sections.write(0, buf)?;
+    writeln!(buf, "pub const {}_LEN: usize = {};", name, table.len())?;
+    writeln!(buf, "pub const {}_CARRIER_FREQ: f64 = {:?};", name, spwm.carrier_freq())?;
+    writeln!(buf, "pub const {}_SIN_FREQ: f64 = {:?};\n", name, spwm.sin_freq())?;
+
+    write_table(buf, args, &name, &name, ty, &table, &value)?;
+    sections.write(1, buf)?;
+
+    if let Some(pad) = args.inverter {
+        let table = spwm.table_not(&table, pad);
+        write_table(buf, args, &format!("{}_NOT", name), &name, ty, &table, &value)?;
+    }
+
+    sections.write(2, buf)?;
+    sections.write_remains(3, buf)?;
+
+    Ok(())
+}
+
+fn write_table(buf: &mut dyn Write, args: &FormatArgs, name: &str, len: &str, ty: &str, table: &[DutyCycle], value: &impl Fn(DutyCycle) -> String) -> Result<()> {
+    let item = match (&args.link_section, args.rust_item) {
+        (Some(section), _) => {
+            writeln!(buf, "#[link_section = \"{}\"]", section.escape_default())?;
+            "static"
+        },
+        (None, RustItem::Static) => "static",
+        (None, RustItem::Const) => "const",
+    };
+    writeln!(buf, "pub {} {}: [{}; {}_LEN] = [", item, name, ty, len)?;
+
+    for row in table.chunks(args.row_width()?) {
+        write!(buf, "    ")?;
+        for val in row {
+            write!(buf, "{}, ", value(*val))?;
+        }
+        writeln!(buf)?;
+    }
+
+    writeln!(buf, "];\n")
+}
@@ -0,0 +1,129 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, Alignment, Pulse, merge_pulses, complement_pulses};
+
+use super::{Format, UserSection, FormatArgs, Language};
+
+/// SPICE subcircuit with piecewise linear gate sources, nodes are `gate`, `gate_n` and `ref`.
+/// `gate_n` is complement of `gate`, with inverter pad as dead time after each fall and before each rise of `gate`
+///
+/// ```text
+/// .include wave.lib
+/// X1 gate gate_n 0 WAVE_50HZ
+/// ```
+#[derive(Default)]
+pub struct SpicePwl;
+
+impl Format for SpicePwl {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        Some("*")
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let end = spwm.num_of_samples() as f64 * spwm.step();
+        let name = args.ident(spwm, Language::C);
+        let align = match args.align {
+            Alignment::Edge => "edge",
+            Alignment::Center => "center",
+        };
+
+        let (gate, gate_n) = gates(spwm, args);
+
+        writeln!(buf, "* {} {}Hz gate signals, carrier {}Hz, pwm top {}, {} aligned", args.name, spwm.sin_freq(), spwm.carrier_freq(), spwm.pwm_top(), align)?;
+        writeln!(buf, "* X1 gate gate_n 0 {}\n", name)?;
+        sections.write(0, buf)?;
+        writeln!(buf, ".subckt {} gate gate_n ref", name)?;
+        write_pwl(buf, "V_gate gate ref", &points(&gate, end, args), args.row_width()?)?;
+        write_pwl(buf, "V_gate_n gate_n ref", &points(&gate_n, end, args), args.row_width()?)?;
+        sections.write(1, buf)?;
+        writeln!(buf, ".ends {}\n", name)?;
+        sections.write_remains(2, buf)?;
+
+        Ok(())
+    }
+}
+
+/// high level intervals of `gate` and `gate_n`, adjacent pulses are merged
+fn gates(spwm: &SPWM, args: &FormatArgs) -> (Vec<Pulse>, Vec<Pulse>) {
+    let table = spwm.lookup_table();
+    let end = table.len() as f64 * spwm.step();
+    let gate = merge_pulses(&spwm.pulses(&table, args.align), spwm.step() * 1e-9);
+    let gate_n = complement_pulses(&gate, end, args.inverter.unwrap_or(0.0));
+    (gate, gate_n)
+}
+
+/// PWL points of high level intervals, edges start at interval bounds and last rise or fall time
+fn points(intervals: &[Pulse], end: f64, args: &FormatArgs) -> Vec<(f64, f64)> {
+    let mut points: Vec<(f64, f64)> = Vec::with_capacity(intervals.len() * 4 + 2);
+    // PWL times must be strictly increasing, edges shorter than rise or fall time are delayed
+    let push = |points: &mut Vec<(f64, f64)>, time: f64, val: f64| {
+        match points.last() {
+            Some((last, _)) if time <= *last => points.push((*last + end * 1e-12, val)),
+            _ => points.push((time, val)),
+        }
+    };
+
+    if intervals.first().map(|x| x.start > 0.0).unwrap_or(true) {
+        push(&mut points, 0.0, args.low);
+    }
+    for pulse in intervals {
+        if pulse.start > 0.0 {
+            push(&mut points, pulse.start, args.low);
+            push(&mut points, pulse.start + args.rise, args.high);
+        }
+        else {
+            push(&mut points, 0.0, args.high);
+        }
+        if pulse.end < end {
+            push(&mut points, pulse.end, args.high);
+            push(&mut points, pulse.end + args.fall, args.low);
+        }
+        else {
+            push(&mut points, end, args.high);
+        }
+    }
+    if points.last().map(|x| x.0 < end).unwrap_or(true) {
+        push(&mut points, end, args.low);
+    }
+    points
+}
+
+fn write_pwl(buf: &mut dyn Write, source: &str, points: &[(f64, f64)], width: usize) -> Result<()> {
+    writeln!(buf, "{} PWL(", source)?;
+    for row in points.chunks(width) {
+        write!(buf, "+")?;
+        for (time, val) in row {
+            write!(buf, " {:e} {}", time, val)?;
+        }
+        writeln!(buf)?;
+    }
+    writeln!(buf, "+ )")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gates_never_high_together() {
+        let spwm = SPWM::new(50.0, 100, 0.0002, 100, 0);
+        for align in [Alignment::Edge, Alignment::Center] {
+            for dead_time in [0.0, 0.00001, 0.00004] {
+                let args = FormatArgs {
+                    align,
+                    inverter: Some(dead_time),
+                    ..Default::default()
+                };
+                let (gate, gate_n) = gates(&spwm, &args);
+                assert!(!gate_n.is_empty());
+                for x in &gate {
+                    for y in &gate_n {
+                        // gate_n rise dead time after gate fall and fall dead time before gate rise
+                        assert!(y.end + dead_time <= x.start + 1e-12 || x.end + dead_time <= y.start + 1e-12,
+                            "gate {:?} and gate_n {:?} are closer than {}", x, y, dead_time);
+                    }
+                }
+            }
+        }
+    }
+}
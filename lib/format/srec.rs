@@ -0,0 +1,183 @@
+use std::io::{Result, Write, Error, ErrorKind};
+
+use crate::SPWM;
+
+use super::{Format, UserSection, FormatArgs};
+
+const MAX_HEADER_LEN: usize     = 252;
+
+/// Motorola S-record file, S1/S2/S3 data records are selected by end address,
+/// inverter table is placed right after the table
+#[derive(Default)]
+pub struct SRecord;
+
+impl Format for SRecord {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let bytes = args.pack_tables(spwm)?;
+
+        let end = args.address as u64 + bytes.len().saturating_sub(1) as u64;
+        if end > u32::MAX as u64 {
+            return Err(Error::new(ErrorKind::InvalidInput, "table does not fit in 32 bit address space of S-record"));
+        }
+        // (data record, termination record, address bytes)
+        let (data_ty, end_ty, addr_len) = if end > 0xFF_FFFF {
+            (3, 7, 4)
+        }
+        else if end > 0xFFFF {
+            (2, 8, 3)
+        }
+        else {
+            (1, 9, 2)
+        };
+        // byte count covers address, data and checksum
+        let max_len = 255 - addr_len - 1;
+        let record_len = args.record_len;
+        if !(1..=max_len).contains(&record_len) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("record length {} of S{} records must be 1 to {}", record_len, data_ty, max_len)
+            ));
+        }
+
+        // header record holds at most 252 data bytes after address and checksum
+        let header = args.name.as_bytes();
+        write_record(buf, 0, 0, 2, &header[..header.len().min(MAX_HEADER_LEN)])?;
+
+        let mut count = 0;
+        for (idx, chunk) in bytes.chunks(record_len).enumerate() {
+            let address = args.address + (idx * record_len) as u32;
+            write_record(buf, data_ty, address, addr_len, chunk)?;
+            count += 1;
+        }
+        if count <= 0xFFFF {
+            write_record(buf, 5, count, 2, &[])?;
+        }
+        else {
+            write_record(buf, 6, count, 3, &[])?;
+        }
+        write_record(buf, end_ty, args.address, addr_len, &[])?;
+
+        Ok(())
+    }
+}
+
+fn write_record(buf: &mut dyn Write, ty: u8, address: u32, addr_len: usize, data: &[u8]) -> Result<()> {
+    let count = (addr_len + data.len() + 1) as u8;
+    let address = &address.to_be_bytes()[4 - addr_len..];
+
+    write!(buf, "S{}{:02X}", ty, count)?;
+    let mut sum = count;
+    for b in address.iter().chain(data) {
+        sum = sum.wrapping_add(*b);
+        write!(buf, "{:02X}", b)?;
+    }
+    writeln!(buf, "{:02X}", !sum)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(ty: u8, address: u32, addr_len: usize, data: &[u8]) -> String {
+        let mut buf = Vec::new();
+        write_record(&mut buf, ty, address, addr_len, data).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn known_records() {
+        assert_eq!(record(0, 0, 2, b"hello     \0\0"), "S00F000068656C6C6F202020202000003C\n");
+        let data = [0x0A, 0x0A, 0x0D, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(record(1, 0x7AF0, 2, &data), "S1137AF00A0A0D0000000000000000000000000061\n");
+        assert_eq!(record(5, 3, 2, &[]), "S5030003F9\n");
+        assert_eq!(record(9, 0, 2, &[]), "S9030000FC\n");
+    }
+
+    #[test]
+    fn s1_s9_pair() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        let table = spwm.lookup_table();
+        let args = FormatArgs {
+            name: "hello".to_string(),
+            address: 0x7AF0,
+            record_len: 4,
+            ..Default::default()
+        };
+
+        let mut buf = Vec::new();
+        SRecord.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+
+        let data: Vec<u8> = table.iter().map(|v| *v as u8).collect();
+        assert_eq!(lines, vec![
+            record(0, 0, 2, b"hello").trim_end(),
+            record(1, 0x7AF0, 2, &data[..4]).trim_end(),
+            record(1, 0x7AF4, 2, &data[4..]).trim_end(),
+            "S5030002FA",
+            "S9037AF092",
+        ]);
+    }
+
+    #[test]
+    fn truncate_long_header() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        let args = FormatArgs {
+            name: "x".repeat(300),
+            ..Default::default()
+        };
+
+        let mut buf = Vec::new();
+        SRecord.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let header = text.lines().next().unwrap();
+        assert_eq!(header, record(0, 0, 2, "x".repeat(MAX_HEADER_LEN).as_bytes()).trim_end());
+        assert!(header.starts_with("S0FF0000"));
+    }
+
+    #[test]
+    fn reject_out_of_range() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        for (record_len, address) in [(0, 0), (253, 0), (252, 0x0001_0000), (251, 0x0100_0000), (16, 0xFFFF_FFFC)] {
+            let args = FormatArgs {
+                record_len,
+                address,
+                ..Default::default()
+            };
+            let err = SRecord.write(&spwm, &mut Vec::new(), &UserSection::new(""), &args).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput, "{} {:X}", record_len, address);
+        }
+
+        for (record_len, address) in [(252, 0), (251, 0x0001_0000), (250, 0x0100_0000), (16, 0xFFFF_FFF8)] {
+            let args = FormatArgs {
+                record_len,
+                address,
+                ..Default::default()
+            };
+            assert!(SRecord.write(&spwm, &mut Vec::new(), &UserSection::new(""), &args).is_ok());
+        }
+    }
+
+    #[test]
+    fn wide_address_records() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        for (address, data_ty, end_ty) in [(0x0001_0000, "S2", "S8"), (0x0800_0000, "S3", "S7")] {
+            let args = FormatArgs {
+                address,
+                ..Default::default()
+            };
+
+            let mut buf = Vec::new();
+            SRecord.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+            let text = String::from_utf8(buf).unwrap();
+            let lines: Vec<_> = text.lines().collect();
+            assert!(lines[1].starts_with(data_ty));
+            assert!(lines[lines.len() - 1].starts_with(end_ty));
+        }
+    }
+}
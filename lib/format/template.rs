@@ -0,0 +1,518 @@
+use std::{io::{Result, Write, Error, ErrorKind}, collections::HashMap, path::Path};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs, Language, CInt, bit_width};
+
+/// Output rendered from user template file with a minimal built-in engine:
+///
+/// | tag                                | description                                       |
+/// |------------------------------------|---------------------------------------------------|
+/// | `{{name}}`                         | variable, e.g. `ident`, `sin_freq`, `len`         |
+/// | `{{value:04X}}`                    | variable with fill, width and `x`, `X`, `b`, `o`  |
+/// | `{{carrier_freq:.2}}`              | float with precision                              |
+/// | `{{#each rows}}...{{/each}}`       | iterate `table`, `table_not`, `rows`, `rows_not`  |
+/// | `{{#if inverter}}...{{else}}...{{/if}}` | conditional, `{{#unless last}}` is negated   |
+/// | `{{section 0}}`                    | user section, kept between generations            |
+/// | `{{! comment }}`                   | ignored                                           |
+///
+/// Items of `table` and `table_not` have `index`, `value`, `time`, `first` and `last`,
+/// items of `rows` and `rows_not` have `index`, `first`, `last` and `values` list of table items.
+/// Lines holding only a block tag are removed from output
+pub struct TemplateFile {
+    template:       Template,
+}
+
+impl TemplateFile {
+    /// parse template source
+    pub fn parse(src: &str) -> Result<Self> {
+        let template = Template::parse(src).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        Ok(Self { template })
+    }
+
+    /// read and parse template file
+    pub fn open(path: &Path) -> Result<Self> {
+        let src = std::fs::read_to_string(path)?;
+        let template = Template::parse(&src)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?;
+        Ok(Self { template })
+    }
+}
+
+impl Format for TemplateFile {
+    fn comment<'a>(&self, args: &'a FormatArgs) -> Option<&'a str> {
+        Some(&args.template_comment)
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let scope = root_scope(spwm, args)?;
+
+        self.template.render(&scope, sections, buf)?;
+        sections.write_remains(self.template.sections + 1, buf)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    List(Vec<Scope>),
+}
+
+type Scope = HashMap<&'static str, Value>;
+
+impl Value {
+    fn truthy(&self) -> bool {
+        match self {
+            Value::Str(s) => !s.is_empty(),
+            Value::Int(v) => *v != 0,
+            Value::Float(v) => *v != 0.0,
+            Value::Bool(v) => *v,
+            Value::List(v) => !v.is_empty(),
+        }
+    }
+}
+
+fn root_scope(spwm: &SPWM, args: &FormatArgs) -> Result<Scope> {
+    let table = spwm.lookup_table();
+    let table_not = args.inverter.map(|pad| spwm.table_not(&table, pad)).unwrap_or_default();
+
+    let mut scope = Scope::new();
+    scope.insert("name", Value::Str(args.name.clone()));
+    scope.insert("ident", Value::Str(args.ident(spwm, Language::C)));
+    scope.insert("sin_freq", Value::Float(spwm.sin_freq()));
+    scope.insert("carrier_freq", Value::Float(spwm.carrier_freq()));
+    scope.insert("step", Value::Float(spwm.step()));
+    scope.insert("num_of_samples", Value::Int(spwm.num_of_samples() as i64));
+    scope.insert("len", Value::Int(table.len() as i64));
+    scope.insert("pwm_top", Value::Int(spwm.pwm_top() as i64));
+    scope.insert("padding", Value::Int(spwm.padding() as i64));
+    scope.insert("bits", Value::Int(bit_width(spwm.pwm_top()) as i64));
+    scope.insert("c_type", Value::Str(CInt::from_top(spwm.pwm_top()).name.to_string()));
+    scope.insert("word_bytes", Value::Int(args.word_width(spwm)?.bytes() as i64));
+    scope.insert("inverter", Value::Bool(args.inverter.is_some()));
+    scope.insert("inverter_pad", Value::Float(args.inverter.unwrap_or(0.0)));
+    scope.insert("rows", Value::List(rows(spwm, &table, args.row_width()?)));
+    scope.insert("rows_not", Value::List(rows(spwm, &table_not, args.row_width()?)));
+    scope.insert("table", Value::List(items(spwm, &table, 0)));
+    scope.insert("table_not", Value::List(items(spwm, &table_not, 0)));
+    Ok(scope)
+}
+
+/// list of table values, index start from offset
+fn items(spwm: &SPWM, table: &[DutyCycle], offset: usize) -> Vec<Scope> {
+    table.iter().enumerate().map(|(idx, v)| {
+        let index = offset + idx;
+        let mut scope = Scope::new();
+        scope.insert("index", Value::Int(index as i64));
+        scope.insert("value", Value::Int(*v as i64));
+        scope.insert("time", Value::Float(index as f64 * spwm.step()));
+        scope.insert("first", Value::Bool(idx == 0));
+        scope.insert("last", Value::Bool(idx + 1 == table.len()));
+        scope
+    }).collect()
+}
+
+fn rows(spwm: &SPWM, table: &[DutyCycle], width: usize) -> Vec<Scope> {
+    let count = table.chunks(width).count();
+    table.chunks(width).enumerate().map(|(idx, row)| {
+        let mut scope = Scope::new();
+        scope.insert("index", Value::Int(idx as i64));
+        scope.insert("first", Value::Bool(idx == 0));
+        scope.insert("last", Value::Bool(idx + 1 == count));
+        scope.insert("values", Value::List(items(spwm, row, idx * width)));
+        scope
+    }).collect()
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Var(String, Option<String>),
+    Each(String, Vec<Node>),
+    If(String, bool, Vec<Node>, Vec<Node>),
+    Section(i32),
+}
+
+/// Parsed template, `sections` is largest user section index or -1
+struct Template {
+    nodes:          Vec<Node>,
+    sections:       i32,
+}
+
+/// open block while parsing
+struct Block {
+    kind:           &'static str,
+    name:           String,
+    negate:         bool,
+    line:           usize,
+    nodes:          Vec<Node>,
+    else_nodes:     Option<Vec<Node>>,
+}
+
+impl Template {
+    fn parse(src: &str) -> std::result::Result<Self, String> {
+        let mut stack: Vec<Block> = vec![Block {
+            kind: "",
+            name: String::new(),
+            negate: false,
+            line: 0,
+            nodes: Vec::new(),
+            else_nodes: None,
+        }];
+        let mut sections = -1;
+
+        for (line_idx, line) in src.split_inclusive('\n').enumerate() {
+            let line_no = line_idx + 1;
+            // line holding only a block tag is replaced by the tag
+            let trimmed = line.trim();
+            let line = if trimmed.starts_with("{{") && trimmed.ends_with("}}") && trimmed.matches("{{").count() == 1
+                && is_block(&trimmed[2..trimmed.len() - 2]) {
+                trimmed
+            }
+            else {
+                line
+            };
+
+            let mut rest = line;
+            while !rest.is_empty() {
+                let Some(start) = rest.find("{{") else {
+                    push(&mut stack, Node::Text(rest.to_string()));
+                    break;
+                };
+                if start > 0 {
+                    push(&mut stack, Node::Text(rest[..start].to_string()));
+                }
+                let end = rest[start..].find("}}")
+                    .ok_or_else(|| format!("line {}: unclosed tag", line_no))?;
+                let tag = rest[start + 2..start + end].trim();
+                rest = &rest[start + end + 2..];
+
+                let (keyword, arg) = match tag.split_once(char::is_whitespace) {
+                    Some((k, a)) => (k, a.trim()),
+                    None => (tag, ""),
+                };
+                match keyword {
+                    "#each" | "#if" | "#unless" => {
+                        if arg.is_empty() {
+                            return Err(format!("line {}: {} needs a variable", line_no, keyword));
+                        }
+                        stack.push(Block {
+                            kind: if keyword == "#each" { "each" } else { "if" },
+                            name: arg.to_string(),
+                            negate: keyword == "#unless",
+                            line: line_no,
+                            nodes: Vec::new(),
+                            else_nodes: None,
+                        });
+                    },
+                    "else" => {
+                        let block = stack.last_mut().filter(|b| b.kind == "if" && b.else_nodes.is_none())
+                            .ok_or_else(|| format!("line {}: else outside of if", line_no))?;
+                        block.else_nodes = Some(Vec::new());
+                    },
+                    "/each" | "/if" | "/unless" => {
+                        let kind = if keyword == "/each" { "each" } else { "if" };
+                        if stack.len() == 1 || stack.last().map(|b| b.kind) != Some(kind) {
+                            return Err(format!("line {}: unexpected {}", line_no, keyword));
+                        }
+                        let block = stack.pop().unwrap_or_else(|| unreachable!());
+                        let node = if kind == "each" {
+                            Node::Each(block.name, block.nodes)
+                        }
+                        else {
+                            Node::If(block.name, block.negate, block.nodes, block.else_nodes.unwrap_or_default())
+                        };
+                        push(&mut stack, node);
+                    },
+                    "section" => {
+                        let index: i32 = arg.parse()
+                            .map_err(|_| format!("line {}: section needs a number", line_no))?;
+                        sections = sections.max(index);
+                        push(&mut stack, Node::Section(index));
+                    },
+                    _ if tag.starts_with('!') => {},
+                    _ => {
+                        let (name, spec) = match tag.split_once(':') {
+                            Some((n, s)) => (n.trim(), Some(s.trim().to_string())),
+                            None => (tag, None),
+                        };
+                        if name.is_empty() {
+                            return Err(format!("line {}: empty tag", line_no));
+                        }
+                        push(&mut stack, Node::Var(name.to_string(), spec));
+                    },
+                }
+            }
+        }
+
+        if stack.len() > 1 {
+            let block = &stack[stack.len() - 1];
+            return Err(format!("line {}: {} {} is not closed", block.line, block.kind, block.name));
+        }
+        let nodes = stack.pop().map(|b| b.nodes).unwrap_or_default();
+        Ok(Self { nodes, sections })
+    }
+
+    fn render(&self, scope: &Scope, sections: &UserSection, out: &mut dyn Write) -> Result<()> {
+        render_nodes(&self.nodes, &mut vec![scope], sections, out)
+    }
+}
+
+/// comments and tags whose first word is a block keyword
+fn is_block(tag: &str) -> bool {
+    let tag = tag.trim();
+    let keyword = tag.split_whitespace().next().unwrap_or_default();
+    tag.starts_with('!') || ["#each", "#if", "#unless", "/each", "/if", "/unless", "else", "section"].contains(&keyword)
+}
+
+fn push(stack: &mut [Block], node: Node) {
+    if let Some(block) = stack.last_mut() {
+        match &mut block.else_nodes {
+            Some(nodes) => nodes.push(node),
+            None => block.nodes.push(node),
+        }
+    }
+}
+
+fn lookup<'a>(stack: &[&'a Scope], name: &str) -> Result<&'a Value> {
+    stack.iter().rev()
+        .find_map(|scope| scope.get(name))
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("unknown template variable `{}`", name)))
+}
+
+fn render_nodes(nodes: &[Node], stack: &mut Vec<&Scope>, sections: &UserSection, out: &mut dyn Write) -> Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.write_all(text.as_bytes())?,
+            Node::Var(name, spec) => {
+                let text = format_value(lookup(stack, name)?, spec.as_deref())
+                    .map_err(|e| Error::new(ErrorKind::InvalidData, format!("`{}`: {}", name, e)))?;
+                out.write_all(text.as_bytes())?;
+            },
+            Node::Each(name, body) => {
+                let Value::List(items) = lookup(stack, name)? else {
+                    return Err(Error::new(ErrorKind::InvalidData, format!("`{}` is not a list", name)));
+                };
+                for item in items {
+                    stack.push(item);
+                    render_nodes(body, stack, sections, out)?;
+                    stack.pop();
+                }
+            },
+            Node::If(name, negate, body, else_body) => {
+                if lookup(stack, name)?.truthy() != *negate {
+                    render_nodes(body, stack, sections, out)?;
+                }
+                else {
+                    render_nodes(else_body, stack, sections, out)?;
+                }
+            },
+            Node::Section(index) => sections.write(*index, out)?,
+        }
+    }
+    Ok(())
+}
+
+/// format value with spec `[0][width][.precision][x|X|b|o]`
+fn format_value(value: &Value, spec: Option<&str>) -> std::result::Result<String, String> {
+    let Some(spec) = spec else {
+        return Ok(match value {
+            Value::Str(s) => s.clone(),
+            Value::Int(v) => v.to_string(),
+            Value::Float(v) => v.to_string(),
+            Value::Bool(v) => v.to_string(),
+            Value::List(v) => v.len().to_string(),
+        });
+    };
+
+    let zero = spec.starts_with('0');
+    let spec = spec.trim_start_matches('0');
+    let (spec, radix) = match spec.chars().last() {
+        Some(c @ ('x' | 'X' | 'b' | 'o')) => (&spec[..spec.len() - 1], Some(c)),
+        _ => (spec, None),
+    };
+    let (width, precision) = match spec.split_once('.') {
+        Some((w, p)) => (w, Some(p.parse::<usize>().map_err(|_| format!("invalid precision in spec \"{}\"", spec))?)),
+        None => (spec, None),
+    };
+    let width = if width.is_empty() {
+        0
+    }
+    else {
+        width.parse::<usize>().map_err(|_| format!("invalid width in spec \"{}\"", spec))?
+    };
+
+    let text = match (value, radix) {
+        (Value::Int(v), Some('x')) => format!("{:x}", v),
+        (Value::Int(v), Some('X')) => format!("{:X}", v),
+        (Value::Int(v), Some('b')) => format!("{:b}", v),
+        (Value::Int(v), Some('o')) => format!("{:o}", v),
+        (_, Some(_)) => return Err("radix spec needs an integer".to_string()),
+        (Value::Float(v), None) => match precision {
+            Some(p) => format!("{:.*}", p, v),
+            None => v.to_string(),
+        },
+        (value, None) => format_value(value, None)?,
+    };
+
+    Ok(if zero {
+        match text.strip_prefix('-') {
+            Some(digits) => format!("-{:0>width$}", digits, width = width.saturating_sub(1)),
+            None => format!("{:0>width$}", text, width = width),
+        }
+    }
+    else if matches!(value, Value::Str(_)) {
+        format!("{:<width$}", text, width = width)
+    }
+    else {
+        format!("{:>width$}", text, width = width)
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(src: &str, scope: &Scope, sections: &UserSection) -> String {
+        let mut buf = Vec::new();
+        Template::parse(src).unwrap().render(scope, sections, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    fn list(values: &[i64]) -> Value {
+        Value::List(values.iter().enumerate().map(|(idx, v)| {
+            let mut scope = Scope::new();
+            scope.insert("value", Value::Int(*v));
+            scope.insert("first", Value::Bool(idx == 0));
+            scope.insert("last", Value::Bool(idx + 1 == values.len()));
+            scope
+        }).collect())
+    }
+
+    #[test]
+    fn reject_broken_blocks() {
+        for src in [
+            "{{#each table}}\n{{value}}\n",
+            "{{#if inverter}}\n{{#each table}}{{/if}}\n",
+            "{{#each table}}{{/unless}}",
+            "{{/each}}",
+            "{{/if}}",
+            "{{else}}",
+            "{{#each table}}{{else}}{{/each}}",
+            "{{#if a}}{{else}}{{else}}{{/if}}",
+            "{{#each}}{{/each}}",
+            "{{value",
+            "{{section x}}",
+            "{{}}",
+        ] {
+            assert!(Template::parse(src).is_err(), "{:?}", src);
+        }
+    }
+
+    #[test]
+    fn render_blocks() {
+        let mut scope = Scope::new();
+        scope.insert("yes", Value::Bool(true));
+        scope.insert("no", Value::Bool(false));
+        scope.insert("empty", Value::List(Vec::new()));
+        scope.insert("table", list(&[1, 2, 3]));
+        let sections = UserSection::new("//");
+
+        assert_eq!(render("{{#if yes}}a{{else}}b{{/if}}", &scope, &sections), "a");
+        assert_eq!(render("{{#if no}}a{{else}}b{{/if}}", &scope, &sections), "b");
+        assert_eq!(render("{{#unless no}}a{{else}}b{{/unless}}", &scope, &sections), "a");
+        assert_eq!(render("{{#unless yes}}a{{/unless}}", &scope, &sections), "");
+        assert_eq!(render("{{#if empty}}a{{else}}b{{/if}}", &scope, &sections), "b");
+        assert_eq!(render("{{#each empty}}a{{/each}}", &scope, &sections), "");
+        assert_eq!(render("{{#each table}}{{value}};{{/each}}", &scope, &sections), "1;2;3;");
+        // lines holding only a block tag are removed
+        assert_eq!(render("{{#each table}}\n  {{value}}\n{{/each}}\n", &scope, &sections), "  1\n  2\n  3\n");
+        assert_eq!(render("{{! note }}\na\n", &scope, &sections), "a\n");
+    }
+
+    #[test]
+    fn keep_lines_of_variables_named_like_blocks() {
+        let mut scope = Scope::new();
+        scope.insert("elsewhere", Value::Int(1));
+        scope.insert("sections", Value::Int(2));
+        scope.insert("if_ready", Value::Int(3));
+        let sections = UserSection::new("//");
+
+        assert_eq!(render("{{elsewhere}}\n{{sections}}\n  {{if_ready}}\n", &scope, &sections), "1\n2\n  3\n");
+    }
+
+    #[test]
+    fn render_first_last() {
+        let mut scope = Scope::new();
+        scope.insert("table", list(&[1, 2, 3]));
+        let sections = UserSection::new("//");
+
+        assert_eq!(render("[{{#each table}}{{value}}{{#unless last}}, {{/unless}}{{/each}}]", &scope, &sections), "[1, 2, 3]");
+        assert_eq!(render("{{#each table}}{{#if first}}>{{/if}}{{value}}{{/each}}", &scope, &sections), ">123");
+    }
+
+    #[test]
+    fn format_specs() {
+        let cases = [
+            (Value::Int(26), "04X", "001A"),
+            (Value::Int(26), "x", "1a"),
+            (Value::Int(5), "08b", "00000101"),
+            (Value::Int(8), "o", "10"),
+            (Value::Int(-7), "04", "-007"),
+            (Value::Int(42), "5", "   42"),
+            (Value::Float(1.23456), ".2", "1.23"),
+            (Value::Float(2.5), "6.1", "   2.5"),
+            (Value::Str("ab".to_string()), "4", "ab  "),
+            (Value::Bool(true), "", "true"),
+        ];
+        for (value, spec, text) in cases {
+            assert_eq!(format_value(&value, Some(spec)).unwrap(), text, "{:?} {}", value, spec);
+        }
+        assert!(format_value(&Value::Float(1.0), Some("x")).is_err());
+        assert!(format_value(&Value::Int(1), Some(".a")).is_err());
+    }
+
+    #[test]
+    fn user_sections_round_trip() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        let args = FormatArgs::default();
+        let template = TemplateFile::parse("// {{name}}\n{{section 0}}\nlen = {{len}}\n{{section 1}}\n").unwrap();
+
+        let mut first = Vec::new();
+        template.write(&spwm, &mut first, &UserSection::new("//"), &args).unwrap();
+        let first = String::from_utf8(first).unwrap();
+
+        let mut sections = UserSection::parse(&first, "//");
+        sections.sections.insert(0, "#include <stdint.h>\n".to_string());
+        sections.sections.insert(1, "int main(void);\n".to_string());
+        sections.sections.insert(4, "orphan\n".to_string());
+
+        let mut second = Vec::new();
+        template.write(&spwm, &mut second, &sections, &args).unwrap();
+        let second = String::from_utf8(second).unwrap();
+        assert!(second.contains("// USER SECTION BEGIN 0\n#include <stdint.h>\n// USER SECTION END 0"));
+        assert!(second.contains("// USER SECTION BEGIN 1\nint main(void);\n// USER SECTION END 1"));
+
+        let parsed = UserSection::parse(&second, "//");
+        assert_eq!(parsed.sections, sections.sections);
+    }
+
+    #[test]
+    fn reject_zero_row_width() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 255, 0);
+        let args = FormatArgs {
+            width: 0,
+            ..Default::default()
+        };
+        let template = TemplateFile::parse("{{#each rows}}{{#each values}}{{value}}{{/each}};{{/each}}").unwrap();
+
+        let err = template.write(&spwm, &mut Vec::new(), &UserSection::new("//"), &args).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,274 @@
+use std::{io::{Result, Write, BufWriter}, fmt, str::FromStr};
+
+use crate::{SPWM, DutyCycle, Alignment, Pulse, merge_pulses, complement_pulses};
+
+use super::{Format, UserSection, FormatArgs, Language, bit_width};
+
+/// VCD timescale, e.g. `1ns` or `100ps`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timescale {
+    pub value:          u32,
+    /// power of ten exponent of unit, e.g. -9 for ns
+    pub exp:            i32,
+}
+
+const UNITS: [(&str, i32); 6] = [("s", 0), ("ms", -3), ("us", -6), ("ns", -9), ("ps", -12), ("fs", -15)];
+
+impl Timescale {
+    /// return timescale in seconds
+    pub fn seconds(&self) -> f64 {
+        self.value as f64 * 10f64.powi(self.exp)
+    }
+}
+
+impl Default for Timescale {
+    fn default() -> Self {
+        Self { value: 1, exp: -9 }
+    }
+}
+
+impl FromStr for Timescale {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (value, unit) = s.split_at(split);
+        let value = match value {
+            "1" => 1,
+            "10" => 10,
+            "100" => 100,
+            _ => return Err("timescale value must be 1, 10 or 100".to_string()),
+        };
+        let exp = UNITS.iter()
+            .find(|(name, _)| *name == unit.trim())
+            .map(|(_, exp)| *exp)
+            .ok_or_else(|| "timescale unit must be one of s, ms, us, ns, ps, fs".to_string())?;
+        Ok(Self { value, exp })
+    }
+}
+
+impl fmt::Display for Timescale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = UNITS.iter().find(|(_, exp)| *exp == self.exp).map(|(name, _)| *name).unwrap_or("s");
+        write!(f, "{}{}", self.value, unit)
+    }
+}
+
+const PWM: usize    = 0;
+const PWM_N: usize  = 1;
+const DUTY: usize   = 2;
+const IDS: [char; 3] = ['!', '"', '#'];
+
+/// Value change dump of gate signals at timer tick resolution (step / pwm top),
+/// `pwm_n` is complement of `pwm` with inverter pad as dead time after each fall and before each rise of `pwm`,
+/// `duty` holds table value of current carrier period
+#[derive(Default)]
+pub struct Vcd;
+
+impl Format for Vcd {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let top = spwm.pwm_top().max(1) as u64;
+        let width = bit_width(spwm.pwm_top());
+        // timescale units of each tick
+        let scale = spwm.step() / top as f64 / args.timescale.seconds();
+        let units = |tick: u64| (tick as f64 * scale).round() as u64;
+
+        let mut buf = BufWriter::new(buf);
+        writeln!(buf, "$version spwm_generator {} $end", env!("CARGO_PKG_VERSION"))?;
+        writeln!(buf, "$comment {} {}Hz, carrier {}Hz, pwm top {}, {} $end",
+            args.name, spwm.sin_freq(), spwm.carrier_freq(), spwm.pwm_top(),
+            match args.align {
+                Alignment::Edge => "edge aligned",
+                Alignment::Center => "center aligned",
+            },
+        )?;
+        writeln!(buf, "$timescale {} $end", args.timescale)?;
+        writeln!(buf, "$scope module {} $end", args.ident(spwm, Language::Verilog))?;
+        writeln!(buf, "$var wire 1 {} pwm $end", IDS[PWM])?;
+        writeln!(buf, "$var wire 1 {} pwm_n $end", IDS[PWM_N])?;
+        writeln!(buf, "$var wire {} {} duty $end", width, IDS[DUTY])?;
+        writeln!(buf, "$upscope $end")?;
+        writeln!(buf, "$enddefinitions $end")?;
+
+        let mut events = vec![(0, PWM, 0), (0, PWM_N, 0)];
+        for (idx, v) in table.iter().enumerate() {
+            events.push((idx as u64 * top, DUTY, (*v).max(0) as u64));
+        }
+        let (pwm, pwm_n) = gates(spwm, &table, args);
+        let end = table.len() as u64 * top;
+        for (signal, pulses) in [(PWM, pwm), (PWM_N, pwm_n)] {
+            for pulse in pulses {
+                // round inwards so dead time is never shortened
+                let (start, stop) = ((pulse.start - 1e-6).ceil() as u64, (pulse.end + 1e-6).floor() as u64);
+                if start < stop {
+                    events.push((start, signal, 1));
+                    if stop < end {
+                        events.push((stop, signal, 0));
+                    }
+                }
+            }
+        }
+        // stable sort keep initial values before changes of same tick
+        events.sort_by_key(|x| x.0);
+
+        let mut dump = Dump { time: None, pending: [0; 3], current: None };
+        for (tick, signal, val) in events {
+            dump.set(&mut buf, units(tick), signal, val)?;
+        }
+        dump.flush(&mut buf)?;
+        writeln!(buf, "#{}", units(end))?;
+        buf.flush()?;
+
+        Ok(())
+    }
+}
+
+/// high level intervals of `pwm` and `pwm_n` in ticks
+fn gates(spwm: &SPWM, table: &[DutyCycle], args: &FormatArgs) -> (Vec<Pulse>, Vec<Pulse>) {
+    let top = spwm.pwm_top().max(1) as u64;
+    let pulses: Vec<Pulse> = table.iter().enumerate().filter_map(|(idx, v)| {
+        let (a, b) = high_ticks(*v, top, args.align);
+        let base = idx as u64 * top;
+        (b > a).then(|| Pulse { start: (base + a) as f64, end: (base + b) as f64 })
+    }).collect();
+    let pwm = merge_pulses(&pulses, 0.0);
+    let dead_time = args.inverter.unwrap_or(0.0) / spwm.step() * top as f64;
+    let pwm_n = complement_pulses(&pwm, (table.len() as u64 * top) as f64, dead_time);
+    (pwm, pwm_n)
+}
+
+/// high level ticks [start, end) of a period
+fn high_ticks(val: DutyCycle, top: u64, align: Alignment) -> (u64, u64) {
+    let v = (val.max(0) as u64).min(top);
+    let start = match align {
+        Alignment::Edge => 0,
+        Alignment::Center => (top - v) / 2,
+    };
+    (start, start + v)
+}
+
+/// collect changes of each timestamp and write signals that differ from dumped values
+struct Dump {
+    time:           Option<u64>,
+    pending:        [u64; 3],
+    current:        Option<[u64; 3]>,
+}
+
+impl Dump {
+    fn set(&mut self, buf: &mut dyn Write, time: u64, signal: usize, val: u64) -> Result<()> {
+        if self.time != Some(time) {
+            self.flush(buf)?;
+            self.time = Some(time);
+        }
+        self.pending[signal] = val;
+        Ok(())
+    }
+
+    fn flush(&mut self, buf: &mut dyn Write) -> Result<()> {
+        let Some(time) = self.time else {
+            return Ok(());
+        };
+        match self.current {
+            None => {
+                writeln!(buf, "#{}", time)?;
+                writeln!(buf, "$dumpvars")?;
+                for (signal, val) in self.pending.iter().enumerate() {
+                    write_value(buf, signal, *val)?;
+                }
+                writeln!(buf, "$end")?;
+            },
+            Some(current) if current != self.pending => {
+                writeln!(buf, "#{}", time)?;
+                for (signal, (old, val)) in current.iter().zip(&self.pending).enumerate() {
+                    if old != val {
+                        write_value(buf, signal, *val)?;
+                    }
+                }
+            },
+            _ => {},
+        }
+        self.current = Some(self.pending);
+        Ok(())
+    }
+}
+
+fn write_value(buf: &mut dyn Write, signal: usize, val: u64) -> Result<()> {
+    if signal == DUTY {
+        writeln!(buf, "b{:b} {}", val, IDS[signal])
+    }
+    else {
+        writeln!(buf, "{}{}", val, IDS[signal])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// replay value changes, return (time, pwm, pwm_n) after each timestamp
+    fn replay(text: &str) -> Vec<(u64, u8, u8)> {
+        let mut states = Vec::new();
+        let (mut time, mut pwm, mut pwm_n) = (None, 0, 0);
+        for line in text.lines() {
+            if let Some(t) = line.strip_prefix('#') {
+                if let Some(t) = time {
+                    states.push((t, pwm, pwm_n));
+                }
+                time = Some(t.parse().unwrap());
+            }
+            else if let Some(v) = line.strip_suffix(IDS[PWM]) {
+                pwm = v.parse().unwrap();
+            }
+            else if let Some(v) = line.strip_suffix(IDS[PWM_N]) {
+                pwm_n = v.parse().unwrap();
+            }
+        }
+        states
+    }
+
+    #[test]
+    fn pwm_n_keep_dead_time() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 100, 0);
+        for align in [Alignment::Edge, Alignment::Center] {
+            let args = FormatArgs {
+                align,
+                inverter: Some(0.00025),
+                timescale: "1us".parse().unwrap(),
+                ..Default::default()
+            };
+            let mut buf = Vec::new();
+            Vcd.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+            let states = replay(&String::from_utf8(buf).unwrap());
+
+            assert!(states.iter().any(|x| x.1 == 1) && states.iter().any(|x| x.2 == 1));
+            assert!(states.iter().all(|x| x.1 == 0 || x.2 == 0), "pwm and pwm_n are high together");
+            // 250us dead time after each fall of pwm
+            for pair in states.windows(2) {
+                if pair[0].1 == 1 && pair[1].1 == 0 {
+                    let fall = pair[1].0;
+                    let rise = states.iter().find(|x| x.0 > fall && (x.1 == 1 || x.2 == 1)).unwrap();
+                    assert!(rise.1 == 1 || rise.0 >= fall + 250, "pwm_n rise {}us after pwm fall at {}", rise.0 - fall, fall);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pwm_n_is_complement_without_inverter() {
+        let spwm = SPWM::new(50.0, 8, 0.0025, 100, 0);
+        let args = FormatArgs {
+            timescale: "1us".parse().unwrap(),
+            ..Default::default()
+        };
+        let mut buf = Vec::new();
+        Vcd.write(&spwm, &mut buf, &UserSection::new(""), &args).unwrap();
+        let states = replay(&String::from_utf8(buf).unwrap());
+        assert!(states.iter().all(|x| x.1 != x.2));
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs, Language, bit_width};
+
+/// How table is described inside ROM module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RomStyle {
+    /// `case` statement on address
+    #[default]
+    Case,
+    /// `initial` block filling a register array
+    Array,
+}
+
+/// Synthesizable Verilog ROM module with synchronous read
+#[derive(Default)]
+pub struct VerilogRom;
+
+impl Format for VerilogRom {
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let table_not = args.inverter.map(|pad| spwm.table_not(&table, pad));
+        let width = bit_width(spwm.pwm_top());
+        let addr_width = addr_width(table.len());
+        let name = args.ident(spwm, Language::Verilog);
+
+        sections.write(0, buf)?;
+        writeln!(buf, "module {} #(", name)?;
+        writeln!(buf, "    parameter WIDTH = {},", width)?;
+        writeln!(buf, "    parameter DEPTH = {},", table.len())?;
+        writeln!(buf, "    parameter ADDR_WIDTH = {}", addr_width)?;
+        writeln!(buf, ") (")?;
+        writeln!(buf, "    input  wire                  clk,")?;
+        writeln!(buf, "    input  wire [ADDR_WIDTH-1:0] addr,")?;
+        if table_not.is_some() {
+            writeln!(buf, "    output reg  [WIDTH-1:0]      data,")?;
+            writeln!(buf, "    output reg  [WIDTH-1:0]      data_not")?;
+        }
+        else {
+            writeln!(buf, "    output reg  [WIDTH-1:0]      data")?;
+        }
+        writeln!(buf, ");\n")?;
+        sections.write(1, buf)?;
+
+        match args.rom_style {
+            RomStyle::Case => {
+                write_case(buf, "data", &table, width)?;
+                if let Some(table_not) = &table_not {
+                    write_case(buf, "data_not", table_not, width)?;
+                }
+            },
+            RomStyle::Array => {
+                write_array(buf, "rom", "data", &table, width)?;
+                if let Some(table_not) = &table_not {
+                    write_array(buf, "rom_not", "data_not", table_not, width)?;
+                }
+            },
+        }
+
+        sections.write(2, buf)?;
+        writeln!(buf, "endmodule\n")?;
+        sections.write_remains(3, buf)?;
+
+        Ok(())
+    }
+}
+
+/// Testbench of Verilog ROM module, reads every address and compares output with table
+#[derive(Default)]
+pub struct VerilogTestbench;
+
+impl Format for VerilogTestbench {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        None
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, _sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let table_not = args.inverter.map(|pad| spwm.table_not(&table, pad));
+        let name = args.ident(spwm, Language::Verilog);
+
+        write_testbench(buf, &name, &table, table_not.as_deref(), bit_width(spwm.pwm_top()), addr_width(table.len()))
+    }
+}
+
+/// number of address bits for depth
+fn addr_width(depth: usize) -> usize {
+    (usize::BITS - depth.saturating_sub(1).leading_zeros()).max(1) as usize
+}
+
+fn write_case(buf: &mut dyn Write, output: &str, table: &[DutyCycle], width: usize) -> Result<()> {
+    writeln!(buf, "    always @(posedge clk) begin")?;
+    writeln!(buf, "        case (addr)")?;
+    for (idx, v) in table.iter().enumerate() {
+        writeln!(buf, "            {}: {} <= {}'d{};", idx, output, width, v)?;
+    }
+    writeln!(buf, "            default: {} <= {}'d0;", output, width)?;
+    writeln!(buf, "        endcase")?;
+    writeln!(buf, "    end\n")
+}
+
+fn write_array(buf: &mut dyn Write, rom: &str, output: &str, table: &[DutyCycle], width: usize) -> Result<()> {
+    writeln!(buf, "    reg [WIDTH-1:0] {} [0:DEPTH-1];\n", rom)?;
+    writeln!(buf, "    initial begin")?;
+    for (idx, v) in table.iter().enumerate() {
+        writeln!(buf, "        {}[{}] = {}'d{};", rom, idx, width, v)?;
+    }
+    writeln!(buf, "    end\n")?;
+    writeln!(buf, "    always @(posedge clk) begin")?;
+    writeln!(buf, "        {} <= {}[addr];", output, rom)?;
+    writeln!(buf, "    end\n")
+}
+
+fn write_testbench(buf: &mut dyn Write, name: &str, table: &[DutyCycle], table_not: Option<&[DutyCycle]>, width: usize, addr_width: usize) -> Result<()> {
+    writeln!(buf, "`timescale 1ns / 1ps\n")?;
+    writeln!(buf, "module {}_tb;\n", name)?;
+    writeln!(buf, "    localparam WIDTH = {};", width)?;
+    writeln!(buf, "    localparam DEPTH = {};", table.len())?;
+    writeln!(buf, "    localparam ADDR_WIDTH = {};\n", addr_width)?;
+    writeln!(buf, "    reg                   clk = 0;")?;
+    writeln!(buf, "    reg  [ADDR_WIDTH-1:0] addr = 0;")?;
+    writeln!(buf, "    wire [WIDTH-1:0]      data;")?;
+    if table_not.is_some() {
+        writeln!(buf, "    wire [WIDTH-1:0]      data_not;")?;
+    }
+    writeln!(buf, "    integer               i;")?;
+    writeln!(buf, "    integer               errors = 0;\n")?;
+    writeln!(buf, "    reg [WIDTH-1:0] expected [0:DEPTH-1];")?;
+    if table_not.is_some() {
+        writeln!(buf, "    reg [WIDTH-1:0] expected_not [0:DEPTH-1];")?;
+    }
+    writeln!(buf)?;
+    if table_not.is_some() {
+        writeln!(buf, "    {} dut (.clk(clk), .addr(addr), .data(data), .data_not(data_not));\n", name)?;
+    }
+    else {
+        writeln!(buf, "    {} dut (.clk(clk), .addr(addr), .data(data));\n", name)?;
+    }
+    writeln!(buf, "    always #5 clk = ~clk;\n")?;
+    writeln!(buf, "    initial begin")?;
+    for (idx, v) in table.iter().enumerate() {
+        writeln!(buf, "        expected[{}] = {}'d{};", idx, width, v)?;
+    }
+    if let Some(table_not) = table_not {
+        for (idx, v) in table_not.iter().enumerate() {
+            writeln!(buf, "        expected_not[{}] = {}'d{};", idx, width, v)?;
+        }
+    }
+    writeln!(buf)?;
+    writeln!(buf, "        for (i = 0; i < DEPTH; i = i + 1) begin")?;
+    writeln!(buf, "            addr = i;")?;
+    writeln!(buf, "            @(posedge clk);")?;
+    writeln!(buf, "            #1;")?;
+    writeln!(buf, "            if (data !== expected[i]) begin")?;
+    writeln!(buf, "                $display(\"ERROR addr %0d: data %0d, expected %0d\", i, data, expected[i]);")?;
+    writeln!(buf, "                errors = errors + 1;")?;
+    writeln!(buf, "            end")?;
+    if table_not.is_some() {
+        writeln!(buf, "            if (data_not !== expected_not[i]) begin")?;
+        writeln!(buf, "                $display(\"ERROR addr %0d: data_not %0d, expected %0d\", i, data_not, expected_not[i]);")?;
+        writeln!(buf, "                errors = errors + 1;")?;
+        writeln!(buf, "            end")?;
+    }
+    writeln!(buf, "        end\n")?;
+    writeln!(buf, "        if (errors == 0)")?;
+    writeln!(buf, "            $display(\"PASS: %0d addresses checked\", DEPTH);")?;
+    writeln!(buf, "        else")?;
+    writeln!(buf, "            $display(\"FAIL: %0d errors\", errors);")?;
+    writeln!(buf, "        $finish;")?;
+    writeln!(buf, "    end\n")?;
+    writeln!(buf, "endmodule")
+}
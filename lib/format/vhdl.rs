@@ -0,0 +1,64 @@
+use std::io::{Result, Write};
+
+use crate::{SPWM, DutyCycle};
+
+use super::{Format, UserSection, FormatArgs, Language, bit_width};
+
+/// VHDL package with constant ROM arrays
+#[derive(Default)]
+pub struct VhdlFile;
+
+impl Format for VhdlFile {
+    fn comment<'a>(&self, _args: &'a FormatArgs) -> Option<&'a str> {
+        Some("--")
+    }
+
+    fn write(&self, spwm: &SPWM, buf: &mut dyn Write, sections: &UserSection, args: &FormatArgs) -> Result<()> {
+        let table = spwm.lookup_table();
+        let width = bit_width(spwm.pwm_top());
+        let name = args.ident(spwm, Language::Vhdl);
+
+        writeln!(buf, "library ieee;")?;
+        writeln!(buf, "use ieee.std_logic_1164.all;")?;
+        writeln!(buf, "use ieee.numeric_std.all;\n")?;
+        sections.write(0, buf)?;
+        writeln!(buf, "package {}_pkg is\n", name)?;
+        writeln!(buf, "    constant SAMPLE_WIDTH : natural := {};", width)?;
+        writeln!(buf, "    constant {}_LEN : natural := {};", name, table.len())?;
+        writeln!(buf, "    constant {}_CARRIER_FREQ : natural := {};\n", name, spwm.carrier_freq().round() as u64)?;
+        writeln!(buf, "    subtype sample_t is unsigned(SAMPLE_WIDTH - 1 downto 0);")?;
+        writeln!(buf, "    type rom_t is array (0 to {}_LEN - 1) of sample_t;\n", name)?;
+        sections.write(1, buf)?;
+
+        write_rom(buf, &name, &table, width, args)?;
+        sections.write(2, buf)?;
+
+        if let Some(pad) = args.inverter {
+            let table = spwm.table_not(&table, pad);
+            write_rom(buf, &format!("{}_NOT", name), &table, width, args)?;
+        }
+
+        sections.write(3, buf)?;
+        writeln!(buf, "end package {}_pkg;\n", name)?;
+        sections.write_remains(4, buf)?;
+
+        Ok(())
+    }
+}
+
+fn write_rom(buf: &mut dyn Write, name: &str, table: &[DutyCycle], width: usize, args: &FormatArgs) -> Result<()> {
+    writeln!(buf, "    constant {} : rom_t := (", name)?;
+    let rows: Vec<String> = table.chunks(args.row_width()?).map(|row| {
+        let row: Vec<String> = row.iter().map(|v| format!("\"{:0width$b}\"", v, width = width)).collect();
+        format!("        {}", row.join(", "))
+    }).collect();
+    // single element aggregate must be named
+    let rows = if table.len() == 1 {
+        vec![format!("        0 => {}", rows[0].trim())]
+    }
+    else {
+        rows
+    };
+    writeln!(buf, "{}", rows.join(",\n"))?;
+    writeln!(buf, "    );\n")
+}
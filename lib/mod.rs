@@ -1,8 +1,14 @@
-
 mod sin_wav;
 mod pwm;
 mod spwm;
+mod pulse;
+mod spectrum;
+mod sim;
+pub mod format;
 
 pub use sin_wav::*;
 pub use pwm::*;
-pub use spwm::*;
\ No newline at end of file
+pub use spwm::*;
+pub use pulse::*;
+pub use spectrum::*;
+pub use sim::*;
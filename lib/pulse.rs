@@ -0,0 +1,179 @@
+use crate::{SPWM, DutyCycle};
+
+use rayon::prelude::*;
+
+/// Position of pulse inside each carrier period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    /// pulse start at beginning of period (sawtooth carrier)
+    #[default]
+    Edge,
+    /// pulse is centered in period (triangle carrier)
+    Center,
+}
+
+/// Single high level pulse of reconstructed PWM wave, times in seconds
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Pulse {
+    pub start:          f64,
+    pub end:            f64,
+}
+
+impl Pulse {
+    /// return pulse width in seconds
+    pub fn width(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// merge pulses that touch or overlap, gaps up to `eps` are closed, pulses must be sorted by start
+pub fn merge_pulses(pulses: &[Pulse], eps: f64) -> Vec<Pulse> {
+    let mut merged: Vec<Pulse> = Vec::with_capacity(pulses.len());
+    for pulse in pulses {
+        match merged.last_mut() {
+            Some(last) if pulse.start - last.end <= eps => last.end = last.end.max(pulse.end),
+            _ => merged.push(*pulse),
+        }
+    }
+    merged
+}
+
+/// high level pulses of complementary switch between 0 and end, pulses repeat after end.
+/// Each low interval of `pulses` is shrunk by dead time on both edges, so complementary switch
+/// rise dead time after `pulses` fall and fall dead time before next rise,
+/// intervals shorter than twice the dead time are dropped. `pulses` must be merged
+///
+/// ```rust
+/// use spwm_generator::*;
+///
+/// let gate = [Pulse { start: 1.0, end: 3.0 }];
+/// let gate_n = complement_pulses(&gate, 10.0, 0.5);
+/// assert_eq!(gate_n, [Pulse { start: 0.0, end: 0.5 }, Pulse { start: 3.5, end: 10.0 }]);
+/// ```
+pub fn complement_pulses(pulses: &[Pulse], end: f64, dead_time: f64) -> Vec<Pulse> {
+    // neighbours of first and last low interval are on the other side of table
+    let high_at_start = pulses.first().map(|x| x.start <= 0.0).unwrap_or(false);
+    let high_at_end = pulses.last().map(|x| x.end >= end).unwrap_or(false);
+
+    let mut out = Vec::with_capacity(pulses.len() + 1);
+    let mut push = |start: f64, stop: f64, after_high: bool, before_high: bool| {
+        let start = if after_high { start + dead_time } else { start };
+        let stop = if before_high { stop - dead_time } else { stop };
+        if stop > start {
+            out.push(Pulse { start, end: stop });
+        }
+    };
+
+    let mut time = 0.0;
+    let mut after_high = high_at_end;
+    for pulse in pulses {
+        if pulse.start > time {
+            push(time, pulse.start, after_high, true);
+        }
+        time = pulse.end;
+        after_high = true;
+    }
+    if time < end {
+        push(time, end, after_high, high_at_start);
+    }
+    out
+}
+
+impl SPWM {
+    /// return duty cycle of a table value in range 0.0 to 1.0
+    pub fn duty(&self, val: DutyCycle) -> f64 {
+        (val as f64 / self.pwm_top() as f64).clamp(0.0, 1.0)
+    }
+
+    /// reconstruct high level pulses of a lookup table
+    /// 
+    /// ```rust
+    /// use spwm_generator::*;
+    /// 
+    /// let spwm = SPWM::new(50.0, 200, 0.0001, 255, 0);
+    /// let table = spwm.lookup_table();
+    /// 
+    /// let pulses = spwm.pulses(&table, Alignment::Center);
+    /// assert!(pulses.len() <= table.len());
+    /// ```
+    pub fn pulses(&self, table: &[DutyCycle], align: Alignment) -> Vec<Pulse> {
+        let step = self.step();
+        table.par_iter().enumerate().filter_map(|(idx, v)| {
+            let w = self.duty(*v) * step;
+            if w <= 0.0 {
+                return None;
+            }
+            let start = match align {
+                Alignment::Edge => idx as f64 * step,
+                Alignment::Center => idx as f64 * step + (step - w) / 2.0,
+            };
+            Some(Pulse { start, end: start + w })
+        }).collect()
+    }
+
+    /// return level of reconstructed PWM wave at given time, 1.0 for high and 0.0 for low,
+    /// table repeat itself after its end
+    pub fn level(&self, table: &[DutyCycle], align: Alignment, time: f64) -> f64 {
+        if table.is_empty() || time < 0.0 {
+            return 0.0;
+        }
+        let step = self.step();
+        let period = (time / step).floor();
+        let pos = time / step - period;
+        let w = self.duty(table[period as usize % table.len()]);
+        let high = match align {
+            Alignment::Edge => pos < w,
+            Alignment::Center => {
+                let pad = (1.0 - w) / 2.0;
+                pos >= pad && pos < pad + w
+            },
+        };
+        if high { 1.0 } else { 0.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// true when any pulse of `a` overlap pulse of `b` or is closer than `gap`
+    fn too_close(a: &[Pulse], b: &[Pulse], gap: f64) -> bool {
+        a.iter().any(|x| b.iter().any(|y| x.start < y.end + gap - 1e-12 && y.start < x.end + gap - 1e-12))
+    }
+
+    #[test]
+    fn complement_keeps_dead_time() {
+        let spwm = SPWM::new(50.0, 100, 0.0002, 100, 0);
+        let table = spwm.lookup_table();
+        for align in [Alignment::Edge, Alignment::Center] {
+            let gate = merge_pulses(&spwm.pulses(&table, align), 1e-12);
+            for dead_time in [0.0, 0.00001, 0.00005] {
+                let gate_n = complement_pulses(&gate, table.len() as f64 * spwm.step(), dead_time);
+                assert!(!gate_n.is_empty());
+                assert!(!too_close(&gate, &gate_n, dead_time));
+            }
+        }
+    }
+
+    #[test]
+    fn complement_without_dead_time_fill_gaps() {
+        let gate = [Pulse { start: 0.0, end: 1.0 }, Pulse { start: 2.0, end: 4.0 }];
+        assert_eq!(complement_pulses(&gate, 5.0, 0.0), [Pulse { start: 1.0, end: 2.0 }, Pulse { start: 4.0, end: 5.0 }]);
+        assert_eq!(complement_pulses(&[], 5.0, 1.0), [Pulse { start: 0.0, end: 5.0 }]);
+    }
+
+    #[test]
+    fn complement_wraps_around_table() {
+        let gate = [Pulse { start: 0.0, end: 1.0 }, Pulse { start: 4.0, end: 6.0 }];
+        assert_eq!(complement_pulses(&gate, 6.0, 0.5), [Pulse { start: 1.5, end: 3.5 }]);
+
+        let gate = [Pulse { start: 2.0, end: 3.0 }];
+        assert_eq!(complement_pulses(&gate, 6.0, 0.5), [Pulse { start: 0.0, end: 1.5 }, Pulse { start: 3.5, end: 6.0 }]);
+    }
+
+    #[test]
+    fn merge_touching_pulses() {
+        let pulses = [Pulse { start: 0.0, end: 1.0 }, Pulse { start: 1.0, end: 2.0 }, Pulse { start: 3.0, end: 4.0 }];
+        assert_eq!(merge_pulses(&pulses, 0.0), [Pulse { start: 0.0, end: 2.0 }, Pulse { start: 3.0, end: 4.0 }]);
+    }
+}
@@ -32,19 +32,24 @@ impl PWM {
         self.pwm_top
     }
 
+    /// return padding of min and max duty cycle
+    pub fn padding(&self) -> DutyCycle {
+        self.padding
+    }
+
     /// This function return a signal value at given time
     /// 
     /// ```rust
     /// use spwm_generator::*;
     /// 
-    /// let wave = SinWave::new(1.0, 1.0, 0.0);
+    /// let wave = SinWave::new(1.0, 1.0);
     /// let pwm = PWM::new(wave, 255, 0);
     /// 
     /// let val = pwm.duty_cycle(0.0);
-    /// assert!(val, 127);
+    /// assert_eq!(val, 127);
     /// 
-    /// let val = pwm.duty_cycle(0.5);
-    /// assert!(val, 255);
+    /// let val = pwm.duty_cycle(0.25);
+    /// assert_eq!(val, 255);
     /// ```
     /// 
     pub fn duty_cycle(&self, time: f64) -> DutyCycle {
@@ -61,7 +66,9 @@ impl PWM {
     /// This function generate a series of samples by start and end time
     /// 
     /// ```rust
-    /// let wave = SinWave::new(1.0, 1.0, 0.0);
+    /// use spwm_generator::*;
+    ///
+    /// let wave = SinWave::new(1.0, 1.0);
     /// let pwm = PWM::new(wave, 255, 0);
     ///
     /// let duty_cycles = pwm.duty_cycles_range(0.0, 1.0, 0.125);
@@ -76,7 +83,9 @@ impl PWM {
     /// This function generate a series of samples
     /// 
     /// ```rust
-    /// let wave = SinWave::new(1.0, 1.0, 0.0);
+    /// use spwm_generator::*;
+    ///
+    /// let wave = SinWave::new(1.0, 1.0);
     /// let pwm = PWM::new(wave, 255, 0);
     ///
     /// let duty_cycles = pwm.duty_cycles_fixed(0.0, 1.0, 8);
@@ -91,7 +100,9 @@ impl PWM {
     /// This function generate a series of samples by number of samples
     /// 
     /// ```rust
-    /// let wave = SinWave::new(1.0, 1.0, 0.0);
+    /// use spwm_generator::*;
+    ///
+    /// let wave = SinWave::new(1.0, 1.0);
     /// let pwm = PWM::new(wave, 255, 0);
     ///
     /// let duty_cycles = pwm.duty_cycles(0.0, 8, 0.125);
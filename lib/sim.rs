@@ -0,0 +1,306 @@
+use std::{f64::consts::PI, io::{Error, ErrorKind, Result}};
+
+use crate::{SPWM, DutyCycle, Alignment};
+
+/// Second order LC output filter
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct LcFilter {
+    /// filter inductance in henry
+    pub inductance:     f64,
+    /// filter capacitance in farad
+    pub capacitance:    f64,
+    /// series resistance of inductor in ohm
+    pub resistance:     f64,
+}
+
+/// Series RL load
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct RlLoad {
+    /// load resistance in ohm
+    pub resistance:     f64,
+    /// load inductance in henry, zero for pure resistive load
+    pub inductance:     f64,
+}
+
+/// Half bridge output stage driving optional LC filter and RL load,
+/// output voltage is measured from midpoint of DC bus
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Circuit {
+    pub bus_voltage:    f64,
+    pub filter:         Option<LcFilter>,
+    pub load:           Option<RlLoad>,
+}
+
+/// Simulated voltage and current traces
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Trace {
+    pub step:           f64,
+    pub time:           Vec<f64>,
+    /// output voltage, across filter capacitor or load
+    pub voltage:        Vec<f64>,
+    /// load current, or inductor current when there is no load
+    pub current:        Vec<f64>,
+}
+
+/// Quality of simulated output over whole sin wave cycles
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Analysis {
+    /// amplitude of fundamental output voltage
+    pub fundamental:    f64,
+    /// peak to peak voltage left after removing DC and fundamental
+    pub ripple:         f64,
+    /// total harmonic distortion of output voltage
+    pub thd:            f64,
+    /// total harmonic distortion of output current
+    pub current_thd:    f64,
+}
+
+/// state of circuit: inductor current, capacitor voltage, load current
+type State = [f64; 3];
+
+impl Circuit {
+    /// create new instance
+    pub fn new(bus_voltage: f64, filter: Option<LcFilter>, load: Option<RlLoad>) -> Self {
+        Self {
+            bus_voltage,
+            filter,
+            load,
+        }
+    }
+
+    /// check circuit parameters, resistance and inductance of load may be zero
+    pub fn validate(&self) -> Result<()> {
+        finite("bus_voltage", self.bus_voltage)?;
+        if let Some(f) = self.filter {
+            positive("filter inductance", f.inductance)?;
+            positive("filter capacitance", f.capacitance)?;
+            non_negative("filter resistance", f.resistance)?;
+        }
+        if let Some(l) = self.load {
+            positive("load resistance", l.resistance)?;
+            non_negative("load inductance", l.inductance)?;
+        }
+        Ok(())
+    }
+
+    fn derivative(&self, source: f64, x: &State) -> State {
+        let [il, vc, io] = *x;
+        match (self.filter, self.load) {
+            (Some(f), load) => {
+                let (dio, io) = match load {
+                    Some(l) if l.inductance > 0.0 => ((vc - l.resistance * io) / l.inductance, io),
+                    Some(l) => (0.0, vc / l.resistance),
+                    None => (0.0, 0.0),
+                };
+                [
+                    (source - f.resistance * il - vc) / f.inductance,
+                    (il - io) / f.capacitance,
+                    dio,
+                ]
+            },
+            (None, Some(l)) if l.inductance > 0.0 => {
+                [0.0, 0.0, (source - l.resistance * io) / l.inductance]
+            },
+            _ => [0.0; 3],
+        }
+    }
+
+    fn output(&self, source: f64, x: &State) -> (f64, f64) {
+        let [il, vc, io] = *x;
+        match (self.filter, self.load) {
+            (Some(_), Some(l)) if l.inductance > 0.0 => (vc, io),
+            (Some(_), Some(l)) => (vc, vc / l.resistance),
+            (Some(_), None) => (vc, il),
+            (None, Some(l)) if l.inductance > 0.0 => (source, io),
+            (None, Some(l)) => (source, source / l.resistance),
+            (None, None) => (source, 0.0),
+        }
+    }
+
+    /// simulate circuit with fixed step RK4 solver, table is played `repeat` times.
+    /// error when circuit parameters or step are not valid
+    /// 
+    /// ```rust
+    /// use spwm_generator::*;
+    /// 
+    /// let spwm = SPWM::new(50.0, 200, 0.0001, 255, 0);
+    /// let table = spwm.lookup_table();
+    /// let circuit = Circuit::new(
+    ///     400.0,
+    ///     Some(LcFilter { inductance: 2e-3, capacitance: 10e-6, resistance: 0.05 }),
+    ///     Some(RlLoad { resistance: 20.0, inductance: 1e-3 }),
+    /// );
+    /// 
+    /// let trace = circuit.simulate(&spwm, &table, Alignment::Center, 1e-6, 1).unwrap();
+    /// let analysis = trace.analyze(spwm.sin_freq());
+    /// assert!(analysis.thd < 0.1);
+    /// ```
+    pub fn simulate(&self, spwm: &SPWM, table: &[DutyCycle], align: Alignment, step: f64, repeat: usize) -> Result<Trace> {
+        self.validate()?;
+        positive("simulation step", step)?;
+        let duration = table.len() as f64 * spwm.step() * repeat as f64;
+        let num_of_steps = (duration / step) as usize;
+        let source = |t: f64| self.bus_voltage * (spwm.level(table, align, t) - 0.5);
+
+        let mut trace = Trace {
+            step,
+            time: Vec::with_capacity(num_of_steps),
+            voltage: Vec::with_capacity(num_of_steps),
+            current: Vec::with_capacity(num_of_steps),
+        };
+        let mut x: State = [0.0; 3];
+
+        for n in 0..num_of_steps {
+            let t = n as f64 * step;
+            // source is sampled at start of step, so switching edges are not smeared between stages
+            let s = source(t);
+            let (v, i) = self.output(s, &x);
+            trace.time.push(t);
+            trace.voltage.push(v);
+            trace.current.push(i);
+
+            let k1 = self.derivative(s, &x);
+            let k2 = self.derivative(s, &add(&x, &k1, step / 2.0));
+            let k3 = self.derivative(s, &add(&x, &k2, step / 2.0));
+            let k4 = self.derivative(s, &add(&x, &k3, step));
+            for j in 0..3 {
+                x[j] += step / 6.0 * (k1[j] + 2.0 * k2[j] + 2.0 * k3[j] + k4[j]);
+            }
+        }
+
+        Ok(trace)
+    }
+}
+
+fn finite(name: &str, val: f64) -> Result<()> {
+    if !val.is_finite() {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("{} must be finite, found {}", name, val)));
+    }
+    Ok(())
+}
+
+fn positive(name: &str, val: f64) -> Result<()> {
+    finite(name, val)?;
+    if val <= 0.0 {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("{} must be positive, found {}", name, val)));
+    }
+    Ok(())
+}
+
+fn non_negative(name: &str, val: f64) -> Result<()> {
+    finite(name, val)?;
+    if val < 0.0 {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("{} must not be negative, found {}", name, val)));
+    }
+    Ok(())
+}
+
+fn add(x: &State, k: &State, h: f64) -> State {
+    [x[0] + k[0] * h, x[1] + k[1] * h, x[2] + k[2] * h]
+}
+
+impl Trace {
+    /// analyze last whole sin wave cycle of trace
+    pub fn analyze(&self, sin_freq: f64) -> Analysis {
+        if sin_freq <= 0.0 || self.voltage.is_empty() {
+            return Analysis::default();
+        }
+        let cycle = ((1.0 / sin_freq) / self.step).round() as usize;
+        let len = self.voltage.len();
+        let start = if cycle > 0 && cycle <= len { len - cycle } else { 0 };
+
+        let voltage = Fit::new(&self.voltage[start..], start, self.step, sin_freq);
+        let current = Fit::new(&self.current[start..], start, self.step, sin_freq);
+
+        let (min, max) = self.voltage[start..].iter().enumerate().fold((f64::MAX, f64::MIN), |(min, max), (idx, v)| {
+            let r = v - voltage.value(start + idx, self.step, sin_freq);
+            (min.min(r), max.max(r))
+        });
+
+        Analysis {
+            fundamental: voltage.amplitude(),
+            ripple: max - min,
+            thd: voltage.thd(),
+            current_thd: current.thd(),
+        }
+    }
+}
+
+/// DC and fundamental component of samples fitted over whole cycles
+struct Fit {
+    dc:                 f64,
+    re:                 f64,
+    im:                 f64,
+    rms:                f64,
+}
+
+impl Fit {
+    fn new(samples: &[f64], start: usize, step: f64, freq: f64) -> Self {
+        let len = samples.len() as f64;
+        let dc = samples.iter().sum::<f64>() / len;
+        let (re, im, sq) = samples.iter().enumerate().fold((0.0, 0.0, 0.0), |(re, im, sq), (idx, v)| {
+            let (s, c) = (2.0 * PI * freq * (start + idx) as f64 * step).sin_cos();
+            let v = v - dc;
+            (re + v * c, im + v * s, sq + v * v)
+        });
+
+        Self {
+            dc,
+            re: 2.0 * re / len,
+            im: 2.0 * im / len,
+            rms: (sq / len).sqrt(),
+        }
+    }
+
+    fn amplitude(&self) -> f64 {
+        (self.re * self.re + self.im * self.im).sqrt()
+    }
+
+    fn value(&self, idx: usize, step: f64, freq: f64) -> f64 {
+        let (s, c) = (2.0 * PI * freq * idx as f64 * step).sin_cos();
+        self.dc + self.re * c + self.im * s
+    }
+
+    /// harmonics rms is everything left after removing fundamental rms from AC rms
+    fn thd(&self) -> f64 {
+        let fundamental = self.amplitude() / 2f64.sqrt();
+        if fundamental == 0.0 {
+            return 0.0;
+        }
+        (self.rms * self.rms - fundamental * fundamental).max(0.0).sqrt() / fundamental
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reject_invalid_parameters() {
+        let spwm = SPWM::new(50.0, 200, 0.0001, 255, 0);
+        let table = spwm.lookup_table();
+        let filter = LcFilter { inductance: 2e-3, capacitance: 10e-6, resistance: 0.0 };
+        let load = RlLoad { resistance: 20.0, inductance: 0.0 };
+
+        let circuits = [
+            Circuit::new(400.0, None, Some(RlLoad { resistance: 0.0, ..load })),
+            Circuit::new(400.0, None, Some(RlLoad { resistance: f64::NAN, ..load })),
+            Circuit::new(400.0, None, Some(RlLoad { inductance: -1e-3, ..load })),
+            Circuit::new(400.0, Some(LcFilter { inductance: 0.0, ..filter }), None),
+            Circuit::new(400.0, Some(LcFilter { capacitance: -1e-6, ..filter }), None),
+            Circuit::new(400.0, Some(LcFilter { resistance: f64::INFINITY, ..filter }), None),
+            Circuit::new(f64::NAN, Some(filter), Some(load)),
+        ];
+        for circuit in circuits {
+            let err = circuit.simulate(&spwm, &table, Alignment::Edge, 1e-6, 1).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+
+        let circuit = Circuit::new(400.0, Some(filter), Some(load));
+        for step in [0.0, -1e-6, f64::NAN] {
+            assert!(circuit.simulate(&spwm, &table, Alignment::Edge, step, 1).is_err());
+        }
+        assert!(circuit.simulate(&spwm, &table, Alignment::Edge, 1e-6, 1).is_ok());
+    }
+}
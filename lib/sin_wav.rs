@@ -7,7 +7,9 @@ use crate::DutyCycle;
 /// Holder for sin wave parameters
 /// 
 /// ```rust
-/// let wave = SinWave::new(50.0f, 1.0f)
+/// use spwm_generator::SinWave;
+///
+/// let wave = SinWave::new(50.0, 1.0);
 /// ```
 ///
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
@@ -75,10 +77,10 @@ impl SinWave {
     /// let wave = SinWave::new(1.0, 1.0);
     /// 
     /// let val = wave.sample(0.0);
-    /// assert!(val, 0.0);
+    /// assert_eq!(val, 0.0);
     /// 
-    /// let val = wave.sample(0.5);
-    /// assert!(val, 1.0);
+    /// let val = wave.sample(0.25);
+    /// assert_eq!(val, 1.0);
     /// ```
     /// 
     pub fn sample(&self, time: f64) -> f64 {
@@ -88,6 +90,8 @@ impl SinWave {
     /// This function generate a series of samples by start and end time
     /// 
     /// ```rust
+    /// use spwm_generator::SinWave;
+    ///
     /// let wave = SinWave::new(1.0, 1.0);
     ///
     /// let samples = wave.samples_range(0.0, 1.0, 0.125);
@@ -102,9 +106,11 @@ impl SinWave {
     /// This function generate a series of samples
     /// 
     /// ```rust
+    /// use spwm_generator::SinWave;
+    ///
     /// let wave = SinWave::new(1.0, 1.0);
     ///
-    /// let samples = wave.samples_fixed(0.0, 1.0, 0.125);
+    /// let samples = wave.samples_fixed(0.0, 1.0, 8);
     /// println!("{:?}", samples);
     /// ```
     pub fn samples_fixed(&self, start_time: f64, end_time: f64, num_samples: DutyCycle) -> Vec<f64> {
@@ -116,6 +122,8 @@ impl SinWave {
     /// This function generate a series of samples by number of samples
     /// 
     /// ```rust
+    /// use spwm_generator::SinWave;
+    ///
     /// let wave = SinWave::new(1.0, 1.0);
     ///
     /// let samples = wave.samples(0.0, 4, 0.125);
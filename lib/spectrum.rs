@@ -0,0 +1,91 @@
+use std::f64::consts::PI;
+
+use crate::{SPWM, DutyCycle, Alignment};
+
+use rayon::prelude::*;
+
+/// Magnitude of one harmonic
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Harmonic {
+    pub order:          usize,
+    pub freq:           f64,
+    pub magnitude:      f64,
+}
+
+/// Harmonic magnitudes of a wave, multiples of fundamental frequency
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Spectrum {
+    pub fundamental:    f64,
+    pub harmonics:      Vec<Harmonic>,
+}
+
+impl Spectrum {
+    /// return magnitude of fundamental harmonic
+    pub fn fundamental_magnitude(&self) -> f64 {
+        self.harmonics.iter()
+            .find(|h| h.order == 1)
+            .map(|h| h.magnitude)
+            .unwrap_or(0.0)
+    }
+
+    /// total harmonic distortion, ratio of harmonics rms to fundamental
+    pub fn thd(&self) -> f64 {
+        let fundamental = self.fundamental_magnitude();
+        if fundamental == 0.0 {
+            return 0.0;
+        }
+        let sum: f64 = self.harmonics.iter()
+            .filter(|h| h.order > 1)
+            .map(|h| h.magnitude * h.magnitude)
+            .sum();
+        sum.sqrt() / fundamental
+    }
+}
+
+impl SPWM {
+    /// calculate spectrum of reconstructed PWM wave up to max_freq,
+    /// magnitudes are relative to half of DC bus (bipolar wave between -1.0 and 1.0)
+    /// 
+    /// ```rust
+    /// use spwm_generator::*;
+    /// 
+    /// let spwm = SPWM::new(50.0, 200, 0.0001, 255, 0);
+    /// let table = spwm.lookup_table();
+    /// 
+    /// let spectrum = spwm.spectrum(&table, Alignment::Edge, 25000.0);
+    /// assert!((spectrum.fundamental_magnitude() - 1.0).abs() < 0.05);
+    /// ```
+    pub fn spectrum(&self, table: &[DutyCycle], align: Alignment, max_freq: f64) -> Spectrum {
+        let duration = table.len() as f64 * self.step();
+        let fundamental = if self.sin_freq() > 0.0 {
+            self.sin_freq()
+        }
+        else {
+            1.0 / duration
+        };
+        let max_order = (max_freq / fundamental) as usize;
+        let pulses = self.pulses(table, align);
+
+        let harmonics = (1..=max_order).into_par_iter()
+            .map(|order| {
+                let w = 2.0 * PI * order as f64 * fundamental;
+                // integral of e^(-jwt) over each pulse
+                let (re, im) = pulses.iter().fold((0.0, 0.0), |(re, im), p| {
+                    let (sa, ca) = (w * p.start).sin_cos();
+                    let (sb, cb) = (w * p.end).sin_cos();
+                    (re + (sb - sa) / w, im + (cb - ca) / w)
+                });
+                Harmonic {
+                    order,
+                    freq: order as f64 * fundamental,
+                    magnitude: 4.0 * (re * re + im * im).sqrt() / duration,
+                }
+            })
+            .collect();
+
+        Spectrum {
+            fundamental,
+            harmonics,
+        }
+    }
+}
@@ -1,5 +1,3 @@
-use std::io::ErrorKind;
-
 use crate::{PWM, SinWave, DutyCycle};
 
 use rayon::prelude::*;
@@ -29,7 +27,7 @@ impl SPWM {
                     (duration / x) as usize
                 }
                 else {
-                    return Err(std::io::Error::new(ErrorKind::Other, "time_step parameter need when num_of_samples not provide"));
+                    return Err(std::io::Error::other("time_step parameter need when num_of_samples not provide"));
                 }
             };
     
@@ -41,7 +39,7 @@ impl SPWM {
                     duration / x as f64
                 }
                 else {
-                    return Err(std::io::Error::new(ErrorKind::Other, "num_of_samples parameter need when time_step not provide"));
+                    return Err(std::io::Error::other("num_of_samples parameter need when time_step not provide"));
                 }
             };
 
@@ -84,6 +82,10 @@ impl SPWM {
         self.pwm.pwm_top()
     }
 
+    pub fn padding(&self) -> DutyCycle {
+        self.pwm.padding()
+    }
+
     pub fn carrier_freq(&self) -> f64 {
         1.0 / self.step
     }
@@ -101,6 +103,17 @@ impl SPWM {
         self.pwm.duty_cycles(0.0, self.num_of_samples, self.step)
     }
 
+    /// generate lookup table of sin wave lagging by phase in degrees, e.g. 120 and 240 for three phase
+    pub fn lookup_table_shifted(&self, phase: f64) -> Vec<DutyCycle> {
+        let start_time = if self.sin_freq() > 0.0 {
+            -phase / (360.0 * self.sin_freq())
+        }
+        else {
+            0.0
+        };
+        self.pwm.duty_cycles(start_time, self.num_of_samples, self.step)
+    }
+
     pub fn table_not(&self, table: &Vec<DutyCycle>, pad: f64) -> Vec<DutyCycle> {
         let p = (pad * self.pwm_top() as f64 / self.step) as DutyCycle;
         table.par_iter().map(|x| {
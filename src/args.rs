@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
-use spwm_generator::DutyCycle;
+use spwm_generator::{DutyCycle, Alignment, format};
+
+use crate::plot::ImageFormat;
 
 
 
@@ -20,11 +22,11 @@ pub struct Args {
     pub pwm_top:            DutyCycle,
     #[clap(short = 'p', long = "padding", default_value = "0", help = "padding for pwm min and max value")]
     pub padding:            usize,
-    #[clap(short = 'o', long = "out", help = "output file path")]
+    #[clap(short = 'o', long = "out", help = "output file path, `-` writes to stdout")]
     pub output:             PathBuf,
     #[clap(short = 'm', long = "format", default_value = "raw", help = "format")]
     pub format:             Format,
-    #[clap(short = 'w', long = "row_width", default_value = "16", help = "number of samples in row")]
+    #[clap(short = 'w', long = "row_width", default_value = "16", value_parser = parse_row_width, help = "number of samples in row")]
     pub row_width:          usize,
     #[clap(short = 'S', long = "separator", default_value = ", ", help = "separator character")]
     pub separator:          String, 
@@ -36,8 +38,106 @@ pub struct Args {
     pub duration:           f64,
     #[clap(short = 'P', long = "plot", help = "plot spwm wave")]
     pub plot:               Option<PlotMode>,
+    #[clap(long = "overlay", help = "draw reference sin wave and carrier on plot")]
+    pub overlay:            bool,
+    #[clap(long = "phases", default_value = "1", help = "number of phases to plot, each phase lag 360 / phases degrees")]
+    pub phases:             usize,
+    #[clap(long = "diff", value_parser = parse_pair, help = "plot difference of two phases, e.g. 0-1 for line to line voltage")]
+    pub diff:               Vec<(usize, usize)>,
+    #[clap(long = "plot_format", default_value = "svg", help = "image format of plots")]
+    pub plot_format:        PlotFormat,
+    #[clap(long = "plot_width", default_value = "1600", help = "image width of plots in pixels")]
+    pub plot_width:         u32,
+    #[clap(long = "plot_height", help = "image height of plots in pixels")]
+    pub plot_height:        Option<u32>,
+    #[clap(long = "plot_start", help = "first sample of wave plot")]
+    pub plot_start:         Option<usize>,
+    #[clap(long = "plot_end", help = "end sample of wave plot, exclusive")]
+    pub plot_end:           Option<usize>,
     #[clap(short = 'I', long = "inverter", help = "inverter mode")]
     pub inverter:           Option<f64>,
+    #[clap(long = "word_width", help = "word width of binary formats [default: smallest that fits pwm_top]")]
+    pub word_width:         Option<WordWidth>,
+    #[clap(long = "endian", default_value = "little", help = "byte order of binary formats")]
+    pub endian:             Endian,
+    #[clap(long = "address", default_value = "0", value_parser = parse_int, help = "start address of binary formats, e.g. 0x08010000")]
+    pub address:            u32,
+    #[clap(long = "record_len", default_value = "16", help = "number of data bytes in each record of hex formats")]
+    pub record_len:         usize,
+    #[clap(long = "header", help = "write header with length, frequencies and CRC before binary table")]
+    pub header:             bool,
+    #[clap(long = "pad_pow2", help = "pad memory depth of FPGA formats to power of two")]
+    pub pad_pow2:           bool,
+    #[clap(long = "rom_style", default_value = "case", help = "table description of verilog ROM module")]
+    pub rom_style:          RomStyle,
+    #[clap(long = "testbench", help = "write testbench of verilog ROM module next to output file")]
+    pub testbench:          bool,
+    #[clap(long = "align", help = "pulse placement of gate signal formats, simulation and spectrum [default: plot mode or pwm]")]
+    pub align:              Option<PlotMode>,
+    #[clap(long = "gate_high", default_value = "1.0", help = "high level of SPICE gate signals in volt")]
+    pub gate_high:          f64,
+    #[clap(long = "gate_low", default_value = "0.0", help = "low level of SPICE gate signals in volt")]
+    pub gate_low:           f64,
+    #[clap(long = "rise_time", default_value = "1e-8", help = "rise time of SPICE gate signals in seconds")]
+    pub rise_time:          f64,
+    #[clap(long = "fall_time", default_value = "1e-8", help = "fall time of SPICE gate signals in seconds")]
+    pub fall_time:          f64,
+    #[clap(long = "timescale", default_value = "1ns", value_parser = parse_timescale, help = "timescale of VCD format, e.g. 10ns or 1ps")]
+    pub timescale:          format::Timescale,
+    #[clap(long = "rust_item", default_value = "const", help = "item kind of Rust tables")]
+    pub rust_item:          RustItem,
+    #[clap(long = "link_section", help = "link section of Rust tables, implies static items")]
+    pub link_section:       Option<String>,
+    #[clap(long = "name_template", default_value = "{name}_{freq}HZ", value_parser = parse_name_template, help = "template of table identifiers, placeholders are {name}, {freq}, {carrier}, {carrier_k}, {samples} and {top}")]
+    pub name_template:      format::NameTemplate,
+    #[clap(long = "name_case", help = "casing of table identifiers [default: upper for rust, keep for others]")]
+    pub name_case:          Option<NameCase>,
+    #[clap(long = "split", help = "write C definitions to output file and declarations to header with same name")]
+    pub split:              bool,
+    #[clap(long = "namespace", default_value = "spwm", help = "namespace of C++ tables")]
+    pub namespace:          String,
+    #[clap(long = "accessor", help = "write constexpr accessor function of each C++ table")]
+    pub accessor:           bool,
+    #[clap(long = "sketch", help = "write Arduino sketch playing table on Timer1 next to output file")]
+    pub sketch:             bool,
+    #[clap(long = "f_cpu", default_value = "16000000", help = "CPU clock of Arduino sketch in Hz")]
+    pub f_cpu:              u32,
+    #[clap(long = "asm_section", help = "section of assembly formats [default: .rodata, .progmem.data for avr-asm]")]
+    pub asm_section:        Option<String>,
+    #[clap(long = "template", help = "template file of template format")]
+    pub template:           Option<PathBuf>,
+    #[clap(long = "template_comment", default_value = "//", help = "line comment marker of user sections in template output")]
+    pub template_comment:   String,
+    #[clap(long = "spectrum", help = "plot spectrum of spwm wave")]
+    pub spectrum:           bool,
+    #[clap(long = "spectrum_log", help = "use log scale for spectrum magnitude")]
+    pub spectrum_log:       bool,
+    #[clap(long = "spectrum_max", help = "max frequency of spectrum plot [default: 2.5 * carrier frequency]")]
+    pub spectrum_max:       Option<f64>,
+    #[clap(long = "preview", help = "draw table in terminal")]
+    pub preview:            bool,
+    #[clap(long = "preview_not", help = "draw inverted table in terminal too")]
+    pub preview_not:        bool,
+    #[clap(long = "html", help = "write html report with plots and table next to output file")]
+    pub html:               bool,
+    #[clap(long = "sim", help = "simulate output filter and load")]
+    pub sim:                bool,
+    #[clap(long = "bus_voltage", default_value = "1.0", help = "DC bus voltage of simulation")]
+    pub bus_voltage:        f64,
+    #[clap(long = "filter_l", help = "LC filter inductance in henry")]
+    pub filter_l:           Option<f64>,
+    #[clap(long = "filter_c", help = "LC filter capacitance in farad")]
+    pub filter_c:           Option<f64>,
+    #[clap(long = "filter_r", default_value = "0.0", help = "LC filter inductor series resistance in ohm")]
+    pub filter_r:           f64,
+    #[clap(long = "load_r", help = "load resistance in ohm")]
+    pub load_r:             Option<f64>,
+    #[clap(long = "load_l", default_value = "0.0", help = "load inductance in henry")]
+    pub load_l:             f64,
+    #[clap(long = "sim_step", help = "simulation time step in seconds [default: carrier period / 100]")]
+    pub sim_step:           Option<f64>,
+    #[clap(long = "sim_repeat", default_value = "8", help = "number of times lookup table played in simulation, only last sin cycle is analyzed so startup transient settles")]
+    pub sim_repeat:         usize,
 }
 
 #[derive(Clone, Copy, ValueEnum)]
@@ -48,10 +148,223 @@ pub enum Format {
     CHex,
     Rust,
     RustHex,
+    IntelHex,
+    Binary,
+    SRecord,
+    #[value(name = "readmemh")]
+    ReadMemH,
+    #[value(name = "readmemb")]
+    ReadMemB,
+    Coe,
+    Mif,
+    Vhdl,
+    Verilog,
+    Csv,
+    Json,
+    Npy,
+    Matlab,
+    Mat,
+    Spice,
+    Vcd,
+    Cpp,
+    Arduino,
+    GnuAsm,
+    #[value(name = "armasm")]
+    ArmAsm,
+    AvrAsm,
+    Template,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum NameCase {
+    Keep,
+    Upper,
+    Lower,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum RustItem {
+    Const,
+    Static,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum RomStyle {
+    Case,
+    Array,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum WordWidth {
+    U8,
+    U16,
+    U32,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Endian {
+    Little,
+    Big,
 }
 
 #[derive(Clone, Copy, ValueEnum)]
 pub enum PlotMode {
-    PWM,
+    Pwm,
     CenterAligned,
 }
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PlotFormat {
+    Svg,
+    Png,
+}
+
+impl From<PlotFormat> for ImageFormat {
+    fn from(format: PlotFormat) -> Self {
+        match format {
+            PlotFormat::Svg => ImageFormat::Svg,
+            PlotFormat::Png => ImageFormat::Png,
+        }
+    }
+}
+
+impl From<WordWidth> for format::WordWidth {
+    fn from(width: WordWidth) -> Self {
+        match width {
+            WordWidth::U8 => format::WordWidth::U8,
+            WordWidth::U16 => format::WordWidth::U16,
+            WordWidth::U32 => format::WordWidth::U32,
+        }
+    }
+}
+
+impl From<Endian> for format::Endian {
+    fn from(endian: Endian) -> Self {
+        match endian {
+            Endian::Little => format::Endian::Little,
+            Endian::Big => format::Endian::Big,
+        }
+    }
+}
+
+impl From<NameCase> for format::NameCase {
+    fn from(case: NameCase) -> Self {
+        match case {
+            NameCase::Keep => format::NameCase::Keep,
+            NameCase::Upper => format::NameCase::Upper,
+            NameCase::Lower => format::NameCase::Lower,
+        }
+    }
+}
+
+impl From<RustItem> for format::RustItem {
+    fn from(item: RustItem) -> Self {
+        match item {
+            RustItem::Const => format::RustItem::Const,
+            RustItem::Static => format::RustItem::Static,
+        }
+    }
+}
+
+impl From<RomStyle> for format::RomStyle {
+    fn from(style: RomStyle) -> Self {
+        match style {
+            RomStyle::Case => format::RomStyle::Case,
+            RomStyle::Array => format::RomStyle::Array,
+        }
+    }
+}
+
+impl From<PlotMode> for Alignment {
+    fn from(mode: PlotMode) -> Self {
+        match mode {
+            PlotMode::Pwm => Alignment::Edge,
+            PlotMode::CenterAligned => Alignment::Center,
+        }
+    }
+}
+
+impl Args {
+    /// pulse placement of gate signals, plot mode is used when not provided
+    pub fn alignment(&self) -> Alignment {
+        self.align.or(self.plot).map(Alignment::from).unwrap_or_default()
+    }
+
+    /// header file included by split C source and Arduino sketch
+    pub fn include(&self) -> Option<PathBuf> {
+        match self.format {
+            Format::C | Format::CHex if self.split => Some(self.output.with_extension("h")),
+            Format::Arduino if self.sketch => Some(self.output.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl From<&Args> for format::FormatArgs {
+    fn from(args: &Args) -> Self {
+        let mut format_args = format::FormatArgs::default();
+        format_args.name = args.name.clone();
+        format_args.separator = args.separator.clone();
+        format_args.width = args.row_width;
+        format_args.inverter = args.inverter;
+        format_args.word_width = args.word_width.map(Into::into);
+        format_args.endian = args.endian.into();
+        format_args.address = args.address;
+        format_args.record_len = args.record_len;
+        format_args.header = args.header;
+        format_args.pad_pow2 = args.pad_pow2;
+        format_args.rom_style = args.rom_style.into();
+        format_args.align = args.alignment();
+        format_args.high = args.gate_high;
+        format_args.low = args.gate_low;
+        format_args.rise = args.rise_time;
+        format_args.fall = args.fall_time;
+        format_args.timescale = args.timescale;
+        format_args.rust_item = args.rust_item.into();
+        format_args.link_section = args.link_section.clone();
+        format_args.name_template = args.name_template.clone();
+        format_args.name_case = args.name_case.map(Into::into);
+        format_args.include = args.include()
+            .map(|path| path.file_name().unwrap_or_default().to_string_lossy().into_owned());
+        format_args.namespace = args.namespace.clone();
+        format_args.accessor = args.accessor;
+        format_args.f_cpu = args.f_cpu;
+        format_args.asm_section = args.asm_section.clone();
+        format_args.template_comment = args.template_comment.clone();
+        format_args
+    }
+}
+
+fn parse_pair(s: &str) -> Result<(usize, usize), String> {
+    let (a, b) = s.split_once('-').ok_or_else(|| format!("expected pair like 0-1, found {}", s))?;
+    let a = a.trim().parse().map_err(|e| format!("{}", e))?;
+    let b = b.trim().parse().map_err(|e| format!("{}", e))?;
+    Ok((a, b))
+}
+
+fn parse_int(s: &str) -> Result<u32, String> {
+    let s = s.trim();
+    let val = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16)
+    }
+    else {
+        s.parse()
+    };
+    val.map_err(|e| format!("{}", e))
+}
+
+fn parse_row_width(s: &str) -> Result<usize, String> {
+    match s.trim().parse() {
+        Ok(0) => Err("row width must be at least 1".to_string()),
+        Ok(width) => Ok(width),
+        Err(e) => Err(format!("{}", e)),
+    }
+}
+
+fn parse_timescale(s: &str) -> Result<format::Timescale, String> {
+    s.parse()
+}
+
+fn parse_name_template(s: &str) -> Result<format::NameTemplate, String> {
+    s.parse()
+}
@@ -0,0 +1,115 @@
+use std::{fs::File, io::{BufWriter, Write}, path::{Path, PathBuf}};
+
+use plotters::prelude::*;
+use spwm_generator::{SPWM, DutyCycle, Spectrum, format::FormatArgs};
+
+use crate::plot::{WavePlot, SpectrumPlot, PlotOptions, PlotResult};
+
+/// Self contained html page with parameters, report, inline svg plots and raw table
+pub struct HtmlReport<'a> {
+    pub spwm:           &'a SPWM,
+    pub args:           &'a FormatArgs,
+    pub wave:           &'a WavePlot,
+    pub spectrum:       &'a SpectrumPlot<'a>,
+}
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 4px 12px; text-align: left; }
+pre { background: #f5f5f5; padding: 1em; overflow-x: auto; }
+svg { max-width: 100%; height: auto; }";
+
+impl HtmlReport<'_> {
+    /// return report path next to output file as `<name>.html`
+    pub fn path(path: &Path) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        path.with_file_name(format!("{}.html", stem))
+    }
+
+    /// write report next to output file as `<name>.html`
+    pub fn write(&self, path: &Path, opts: &PlotOptions) -> PlotResult {
+        let path = Self::path(path);
+
+        let spwm = self.spwm;
+        let table = spwm.lookup_table();
+
+        let mut wave_svg = String::new();
+        {
+            let root = SVGBackend::with_string(&mut wave_svg, opts.size(self.wave.default_height())).into_drawing_area();
+            self.wave.draw(root, spwm, opts)?;
+        }
+        let mut spectrum_svg = String::new();
+        {
+            let root = SVGBackend::with_string(&mut spectrum_svg, opts.size(600)).into_drawing_area();
+            self.spectrum.draw(root, spwm)?;
+        }
+
+        let mut buf = BufWriter::new(File::create(path)?);
+        writeln!(buf, "<!DOCTYPE html>")?;
+        writeln!(buf, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(buf, "<title>{} {} Hz</title>", escape(&self.args.name), spwm.sin_freq())?;
+        writeln!(buf, "<style>\n{}\n</style>\n</head>\n<body>", STYLE)?;
+        writeln!(buf, "<h1>{} {} Hz</h1>", escape(&self.args.name), spwm.sin_freq())?;
+
+        writeln!(buf, "<h2>Parameters</h2>\n<table>")?;
+        row(&mut buf, "Sin Frequency", format!("{} Hz", spwm.sin_freq()))?;
+        row(&mut buf, "Carrier Frequency", format!("{} Hz", spwm.carrier_freq()))?;
+        row(&mut buf, "Time Step", format!("{} s", spwm.step()))?;
+        row(&mut buf, "Number of Samples", spwm.num_of_samples())?;
+        row(&mut buf, "PWM Top", spwm.pwm_top())?;
+        row(&mut buf, "Padding", spwm.padding())?;
+        if let Some(pad) = self.args.inverter {
+            row(&mut buf, "Inverter Dead Time", format!("{} s", pad))?;
+        }
+        writeln!(buf, "</table>")?;
+
+        let spectrum: &Spectrum = self.spectrum.spectrum;
+        let min = table.iter().min().copied().unwrap_or_default();
+        let max = table.iter().max().copied().unwrap_or_default();
+        writeln!(buf, "<h2>Report</h2>\n<table>")?;
+        row(&mut buf, "Table Length", table.len())?;
+        row(&mut buf, "Duration", format!("{} s", table.len() as f64 * spwm.step()))?;
+        row(&mut buf, "Min Duty Cycle", format!("{} ({:.2}%)", min, spwm.duty(min) * 100.0))?;
+        row(&mut buf, "Max Duty Cycle", format!("{} ({:.2}%)", max, spwm.duty(max) * 100.0))?;
+        row(&mut buf, "Fundamental Magnitude", format!("{:.4}", spectrum.fundamental_magnitude()))?;
+        row(&mut buf, "THD", format!("{:.3}%", spectrum.thd() * 100.0))?;
+        writeln!(buf, "</table>")?;
+
+        writeln!(buf, "<h2>Wave</h2>\n{}", wave_svg)?;
+        writeln!(buf, "<h2>Spectrum</h2>\n{}", spectrum_svg)?;
+
+        writeln!(buf, "<h2>Table</h2>")?;
+        write_table(&mut buf, &table, self.args)?;
+        if let Some(pad) = self.args.inverter {
+            writeln!(buf, "<h2>Inverted Table</h2>")?;
+            write_table(&mut buf, &spwm.table_not(&table, pad), self.args)?;
+        }
+
+        writeln!(buf, "</body>\n</html>")?;
+        buf.flush()?;
+
+        Ok(())
+    }
+}
+
+fn row(buf: &mut impl Write, key: &str, val: impl ToString) -> std::io::Result<()> {
+    writeln!(buf, "<tr><th>{}</th><td>{}</td></tr>", key, escape(&val.to_string()))
+}
+
+fn write_table(buf: &mut impl Write, table: &[DutyCycle], args: &FormatArgs) -> std::io::Result<()> {
+    writeln!(buf, "<pre>")?;
+    for row in table.chunks(args.row_width()?) {
+        for val in row {
+            write!(buf, "{:3}{}", val, escape(&args.separator))?;
+        }
+        writeln!(buf)?;
+    }
+    writeln!(buf, "</pre>")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,17 +1,17 @@
-use std::{io::{BufWriter}, fs::File, path::PathBuf, fmt::format};
+use std::{fs::File, path::{Path, PathBuf}, io::{BufWriter, Write, ErrorKind}};
 
-use args::{Args, PlotMode};
+use args::Args;
 use clap::Parser;
-use spwm_generator::*;
-use plotters::prelude::*;
-use rayon::prelude::*;
+use spwm_generator::{*, format::*};
 
 mod args;
-mod format;
+mod plot;
+mod html;
+mod preview;
 
-use format::*;
-
-const INVERTER_OFFSET: f32 = 1.5;
+use plot::*;
+use html::*;
+use preview::*;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
@@ -44,11 +44,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         args.padding
     );
     
-    let mut fs = File::options()
-        .read(true)
-        .write(true)
-        .create(true)
-        .open(&args.output)?;
+    if args.split && args.output.with_extension("h") == args.output {
+        return Err(std::io::Error::new(ErrorKind::InvalidInput, "output of split C file must not be a header").into());
+    }
+
+    if args.html && HtmlReport::path(&args.output) == args.output {
+        return Err(std::io::Error::new(ErrorKind::InvalidInput, "html report would overwrite output file, use another output extension").into());
+    }
+
+    let stdout = args.output == Path::new("-");
+    if stdout && (args.split || args.testbench || args.sketch || args.preview ||
+        args.plot.is_some() || args.spectrum || args.html || args.sim)
+    {
+        return Err(std::io::Error::new(ErrorKind::InvalidInput, "stdout output can not be used with side files, plots or preview").into());
+    }
+
+    let mut template = None;
     let writer: &dyn Format = match args.format {
         args::Format::Raw => &Raw,
         args::Format::RawHex => &RawHex,
@@ -56,156 +67,192 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         args::Format::CHex => &CHexFile,
         args::Format::Rust => &RustFile,
         args::Format::RustHex => &RustHexFile,
+        args::Format::IntelHex => &IntelHex,
+        args::Format::Binary => &Binary,
+        args::Format::SRecord => &SRecord,
+        args::Format::ReadMemH => &ReadMemH,
+        args::Format::ReadMemB => &ReadMemB,
+        args::Format::Coe => &Coe,
+        args::Format::Mif => &Mif,
+        args::Format::Vhdl => &VhdlFile,
+        args::Format::Verilog => &VerilogRom,
+        args::Format::Csv => &Csv,
+        args::Format::Json => &Json,
+        args::Format::Npy => &Npy,
+        args::Format::Matlab => &MatlabScript,
+        args::Format::Mat => &MatFile,
+        args::Format::Spice => &SpicePwl,
+        args::Format::Vcd => &Vcd,
+        args::Format::Cpp => &CppFile,
+        args::Format::Arduino => &ArduinoFile,
+        args::Format::GnuAsm => &GnuAsm,
+        args::Format::ArmAsm => &ArmAsm,
+        args::Format::AvrAsm => &AvrAsm,
+        args::Format::Template => {
+            let path = args.template.as_ref()
+                .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "template format needs --template file"))?;
+            &*template.insert(TemplateFile::open(path)?)
+        },
     };
-    let format_args = FormatArgs {
-        name: args.name,
-        separator: args.separator,
-        width: args.row_width,
-        inverter: args.inverter,
+    // secondary output written next to output file
+    let side_output: Option<(&dyn Format, PathBuf)> = match args.format {
+        args::Format::C | args::Format::CHex if args.split => Some((&CHeader, args.output.with_extension("h"))),
+        args::Format::Verilog if args.testbench => {
+            let stem = args.output.file_stem().unwrap_or_default().to_string_lossy();
+            let ext = args.output.extension().unwrap_or_default().to_string_lossy();
+            Some((&VerilogTestbench, args.output.with_file_name(format!("{}_tb.{}", stem, ext))))
+        },
+        args::Format::Arduino if args.sketch => Some((&ArduinoSketch, args.output.with_extension("ino"))),
+        _ => None,
+    };
+    let align = args.alignment();
+    let format_args = FormatArgs::from(&args);
+
+    if stdout {
+        let mut buf = BufWriter::new(std::io::stdout().lock());
+        let sections = UserSection::new(writer.comment(&format_args).unwrap_or_default());
+        writer.write(&spwm, &mut buf, &sections, &format_args)?;
+        buf.flush()?;
+        return Ok(());
+    }
+    writer.write_file(&spwm, &args.output, &format_args)?;
+    if let Some((side_writer, path)) = &side_output {
+        side_writer.write_file(&spwm, path, &format_args)?;
+    }
+
+    let plot_options = PlotOptions {
+        format: args.plot_format.into(),
+        width: args.plot_width,
+        height: args.plot_height,
+        start: args.plot_start,
+        end: args.plot_end,
     };
-    writer.write(&spwm, &mut fs, &format_args)?;
+
+    if args.preview {
+        let table = spwm.lookup_table();
+        let title = format!("{} {} Hz", args.name, spwm.sin_freq());
+        Preview::new(&title, &table, spwm.pwm_top()).write(&mut std::io::stdout())?;
+        if args.preview_not {
+            let pad = args.inverter.unwrap_or(0.0);
+            let table_not = spwm.table_not(&table, pad);
+            let title = format!("{} {} Hz Inverted", args.name, spwm.sin_freq());
+            Preview::new(&title, &table_not, spwm.pwm_top()).write(&mut std::io::stdout())?;
+        }
+    }
 
     if let Some(mode) = args.plot {
-        plot_wave(mode, &spwm, &args.output, &format_args)?;
+        wave_plot(&args, &spwm, mode.into())?.plot(&spwm, &args.output, &plot_options)?;
     }
 
-    Ok(())
-}
+    if args.spectrum || args.html {
+        let max_freq = args.spectrum_max.unwrap_or(spwm.carrier_freq() * 2.5);
+        let table = spwm.lookup_table();
+        let spectrum = spwm.spectrum(&table, align, max_freq);
+        let spectrum_plot = SpectrumPlot {
+            spectrum: &spectrum,
+            max_freq,
+            log: args.spectrum_log,
+        };
+        if args.spectrum {
+            spectrum_plot.plot(&spwm, &args.output, &plot_options)?;
+        }
+        if args.html {
+            HtmlReport {
+                spwm: &spwm,
+                args: &format_args,
+                wave: &wave_plot(&args, &spwm, align)?,
+                spectrum: &spectrum_plot,
+            }.write(&args.output, &plot_options)?;
+        }
+    }
 
-fn plot_wave(mode: PlotMode, spwm: &SPWM, path: &PathBuf, args: &FormatArgs) -> Result<(), Box<dyn std::error::Error>> {
-    //let path = path.ancestors().nth(1).unwrap().join(format!("Wave_{}.svg", spwm.sin_freq()));
-    let mut path = path.clone();
-    path.set_extension("svg");
+    if args.sim {
+        let filter = match (args.filter_l, args.filter_c) {
+            (Some(inductance), Some(capacitance)) => Some(LcFilter {
+                inductance,
+                capacitance,
+                resistance: args.filter_r,
+            }),
+            (None, None) => None,
+            _ => return Err(std::io::Error::new(ErrorKind::InvalidInput, "filter_l and filter_c parameters need together").into()),
+        };
+        let load = args.load_r.map(|resistance| RlLoad {
+            resistance,
+            inductance: args.load_l,
+        });
+        if filter.is_none() && load.is_none() {
+            return Err(std::io::Error::new(ErrorKind::InvalidInput, "simulation need LC filter or load parameters").into());
+        }
 
-    let table = spwm.lookup_table();
-    let len = table.len() as f32;
+        let step = args.sim_step.unwrap_or(spwm.step() / 100.0);
+        let circuit = Circuit::new(args.bus_voltage, filter, load);
+        let table = spwm.lookup_table();
+        let trace = circuit.simulate(&spwm, &table, align, step, args.sim_repeat.max(1))?;
+        let analysis = trace.analyze(spwm.sin_freq());
 
-    let root = SVGBackend::new(&path, (len as u32 * 100 + 200, 480)).into_drawing_area();
-    let caption = format!("SPWM Wave {} Hz", spwm.sin_freq());
+        println!("Fundamental: {:.4} V", analysis.fundamental);
+        println!("Ripple:      {:.4} Vpp", analysis.ripple);
+        println!("THD Voltage: {:.3} %", analysis.thd * 100.0);
+        println!("THD Current: {:.3} %", analysis.current_thd * 100.0);
+
+        write_trace(&trace, &args.output)?;
+        TracePlot {
+            trace: &trace,
+            analysis: &analysis,
+        }.plot(&spwm, &args.output, &plot_options)?;
+    }
 
-    let min_val = if let Some(_) = args.inverter {
-        -INVERTER_OFFSET
+    Ok(())
+}
+
+/// build stacked phase channels, inverted channel of each phase is stacked right after it
+fn wave_plot(args: &Args, spwm: &SPWM, align: Alignment) -> Result<WavePlot, Box<dyn std::error::Error>> {
+    let phases = args.phases.max(1);
+    let names = if phases == 3 {
+        vec!["U".to_string(), "V".to_string(), "W".to_string()]
+    }
+    else if phases == 1 {
+        vec!["PWM".to_string()]
     }
     else {
-        0.0
+        (0..phases).map(|x| format!("CH{}", x)).collect()
     };
+    let stride = if args.inverter.is_some() { 2 } else { 1 };
 
-    // generate samples
-    let samples: Vec<(f32, f32)> = table.par_iter().enumerate().flat_map(|(idx, v)| {
-        if *v != 0 {
-            let w = *v as f32 / spwm.pwm_top() as f32;
-            match mode {
-                PlotMode::PWM => {
-                    vec![
-                        (idx as f32, 1f32),
-                        (idx as f32 + w, 1f32),
-                        (idx as f32 + w, 0f32),
-                        (idx as f32 + 1f32, 0f32),
-                    ]
-                },
-                PlotMode::CenterAligned => {
-                    let pad = (1f32 - w) / 2f32;
-                    vec![
-                        (idx as f32, 0f32),
-                        (idx as f32 + pad, 0f32),
-                        (idx as f32 + pad, 1f32),
-                        (idx as f32 + pad + w, 1f32),
-                        (idx as f32 + pad + w, 0f32),
-                        (idx as f32 + 1f32, 0f32),
-                    ]
-                },
-            }
-        }
-        else if *v == spwm.pwm_top() {
-            vec![
-                (idx as f32, 1f32),
-                (idx as f32 + 1f32, 1f32),
-            ]
+    let mut wave = WavePlot {
+        align,
+        overlay: args.overlay,
+        ..Default::default()
+    };
+    for (idx, name) in names.into_iter().enumerate() {
+        let table = spwm.lookup_table_shifted(360.0 * idx as f64 / phases as f64);
+        if let Some(pad) = args.inverter {
+            let table_not = spwm.table_not(&table, pad);
+            wave.channels.push(Channel::new(name.clone(), table));
+            wave.channels.push(Channel::new(format!("{} Inverted", name), table_not));
         }
         else {
-            vec![
-                (idx as f32, 0f32),
-                (idx as f32 + 1f32, 0f32),
-            ]
+            wave.channels.push(Channel::new(name, table));
         }
-    }).collect();
-
-    let samples_inverted: Option<Vec<(f32, f32)>> = if let Some(pad) = args.inverter {
-        let table_not = spwm.table_not(&table, pad);
-        Some(table_not.par_iter().enumerate().flat_map(|(idx, v)| {
-            if *v != 0 {
-                let w = *v as f32 / spwm.pwm_top() as f32;
-                match mode {
-                    PlotMode::PWM => {
-                        vec![
-                            (idx as f32, 1f32 - INVERTER_OFFSET),
-                            (idx as f32 + w, 1f32 - INVERTER_OFFSET),
-                            (idx as f32 + w, 0f32 - INVERTER_OFFSET),
-                            (idx as f32 + 1f32, 0f32 - INVERTER_OFFSET),
-                        ]
-                    },
-                    PlotMode::CenterAligned => {
-                        let pad = (1f32 - w) / 2f32;
-                        vec![
-                            (idx as f32, 0f32 - INVERTER_OFFSET),
-                            (idx as f32 + pad, 0f32 - INVERTER_OFFSET),
-                            (idx as f32 + pad, 1f32 - INVERTER_OFFSET),
-                            (idx as f32 + pad + w, 1f32 - INVERTER_OFFSET),
-                            (idx as f32 + pad + w, 0f32 - INVERTER_OFFSET),
-                            (idx as f32 + 1f32, 0f32 - INVERTER_OFFSET),
-                        ]
-                    },
-                }
-            }
-            else if *v == spwm.pwm_top() {
-                vec![
-                    (idx as f32, 1f32 - INVERTER_OFFSET),
-                    (idx as f32 + 1f32, 1f32 - INVERTER_OFFSET),
-                ]
-            }
-            else {
-                vec![
-                    (idx as f32, 0f32 - INVERTER_OFFSET),
-                    (idx as f32 + 1f32, 0f32 - INVERTER_OFFSET),
-                ]
-            }
-        }).collect())
     }
-    else {
-        None
-    };
-
-    root.fill(&WHITE)?;
-    let mut chart = ChartBuilder::on(&root)
-        .caption(&caption, ("sans-serif", 50).into_font())
-        .margin(5)
-        .x_label_area_size(30)
-        .y_label_area_size(30)
-        .build_cartesian_2d(0f32..len, min_val..1f32)?;
-
-    chart.configure_mesh().draw()?;
-
-    chart
-        .draw_series(LineSeries::new(
-            samples,
-            &RED,
-        ))?;
-
-    if let Some(inverted) = samples_inverted {
-        chart
-        .draw_series(LineSeries::new(
-            inverted,
-            &BLUE,
-        ))?;
+    for (a, b) in args.diff.iter().copied() {
+        if a >= phases || b >= phases {
+            return Err(std::io::Error::new(ErrorKind::InvalidInput, "diff phase index out of range").into());
+        }
+        wave.differences.push((a * stride, b * stride));
     }
 
-    chart
-        .configure_series_labels()
-        .background_style(&WHITE.mix(0.8))
-        .border_style(&BLACK)
-        .draw()?;
+    Ok(wave)
+}
 
-    root.present()?;
+fn write_trace(trace: &Trace, path: &Path) -> std::io::Result<()> {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let path = path.with_file_name(format!("{}_sim.csv", stem));
 
-    Ok(())
+    let mut buf = BufWriter::new(File::create(path)?);
+    writeln!(buf, "time,voltage,current")?;
+    for ((t, v), i) in trace.time.iter().zip(&trace.voltage).zip(&trace.current) {
+        writeln!(buf, "{:e},{:e},{:e}", t, v, i)?;
+    }
+    buf.flush()
 }
@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+mod wave;
+mod spectrum;
+mod sim;
+
+pub use wave::*;
+pub use spectrum::*;
+pub use sim::*;
+
+pub type PlotResult = Result<(), Box<dyn std::error::Error>>;
+
+/// Draw on backend selected by image format, closure is expanded for each backend type
+macro_rules! draw_on {
+    ($opts:expr, $path:expr, $size:expr, $draw:expr) => {
+        match $opts.format {
+            $crate::plot::ImageFormat::Svg => $draw(SVGBackend::new(&$path, $size).into_drawing_area()),
+            $crate::plot::ImageFormat::Png => $draw(BitMapBackend::new(&$path, $size).into_drawing_area()),
+        }
+    };
+}
+
+pub(crate) use draw_on;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+/// Output image options shared by all plots
+#[derive(Debug, Clone, Copy)]
+pub struct PlotOptions {
+    pub format:         ImageFormat,
+    pub width:          u32,
+    pub height:         Option<u32>,
+    pub start:          Option<usize>,
+    pub end:            Option<usize>,
+}
+
+impl PlotOptions {
+    /// return image path next to output file with given suffix
+    pub fn path(&self, path: &Path, suffix: &str) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let ext = match self.format {
+            ImageFormat::Svg => "svg",
+            ImageFormat::Png => "png",
+        };
+        path.with_file_name(format!("{}{}.{}", stem, suffix, ext))
+    }
+
+    /// return image size, default height is used when user not provide it
+    pub fn size(&self, height: u32) -> (u32, u32) {
+        (self.width, self.height.unwrap_or(height))
+    }
+
+    /// return range of samples to plot, range holds at least one sample of non empty table
+    pub fn window(&self, len: usize) -> (usize, usize) {
+        if len == 0 {
+            return (0, 0);
+        }
+        let start = self.start.unwrap_or(0).min(len - 1);
+        let end = self.end.unwrap_or(len).clamp(start + 1, len);
+        (start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: Option<usize>, end: Option<usize>, len: usize) -> (usize, usize) {
+        PlotOptions {
+            format: ImageFormat::Svg,
+            width: 1024,
+            height: None,
+            start,
+            end,
+        }.window(len)
+    }
+
+    #[test]
+    fn window_holds_one_sample() {
+        assert_eq!(window(None, None, 100), (0, 100));
+        assert_eq!(window(Some(10), Some(40), 100), (10, 40));
+        assert_eq!(window(None, Some(0), 100), (0, 1));
+        assert_eq!(window(Some(20), Some(10), 100), (20, 21));
+        assert_eq!(window(Some(200), Some(300), 100), (99, 100));
+        assert_eq!(window(Some(5), None, 0), (0, 0));
+    }
+}
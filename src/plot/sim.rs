@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use plotters::{prelude::*, coord::Shift};
+use spwm_generator::{SPWM, Trace, Analysis};
+
+use super::{PlotOptions, PlotResult, draw_on};
+
+/// Output voltage and load current of simulation
+pub struct TracePlot<'a> {
+    pub trace:          &'a Trace,
+    pub analysis:       &'a Analysis,
+}
+
+impl TracePlot<'_> {
+    /// write plot next to output file as `<name>_sim`
+    pub fn plot(&self, spwm: &SPWM, path: &Path, opts: &PlotOptions) -> PlotResult {
+        let path = opts.path(path, "_sim");
+        draw_on!(opts, path, opts.size(900), |root| self.draw(root, spwm))
+    }
+
+    pub fn draw<DB: DrawingBackend>(&self, root: DrawingArea<DB, Shift>, spwm: &SPWM) -> PlotResult
+    where
+        DB::ErrorType: 'static,
+    {
+        let trace = self.trace;
+        let end = trace.time.last().copied().unwrap_or_default() as f32;
+        let range = |v: &[f64]| {
+            let (min, max) = v.iter().fold((f64::MAX, f64::MIN), |(min, max), x| (min.min(*x), max.max(*x)));
+            let pad = ((max - min) * 0.05).max(1e-9);
+            (min - pad) as f32..(max + pad) as f32
+        };
+        // plotting every sample of a long trace makes huge files, so decimate
+        let stride = (trace.time.len() / 20000).max(1);
+
+        root.fill(&WHITE)?;
+        let (_, height) = root.dim_in_pixel();
+        let (upper, lower) = root.split_vertically(height / 2);
+
+        let caption = format!("Output {} Hz, Ripple {:.3} Vpp, THD {:.2}%", spwm.sin_freq(), self.analysis.ripple, self.analysis.thd * 100.0);
+        let mut chart = ChartBuilder::on(&upper)
+            .caption(&caption, ("sans-serif", 30).into_font())
+            .margin(5)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0f32..end, range(&trace.voltage))?;
+        chart.configure_mesh().y_desc("Voltage (V)").draw()?;
+        chart.draw_series(LineSeries::new(
+            trace.time.iter().zip(&trace.voltage).step_by(stride).map(|(t, v)| (*t as f32, *v as f32)),
+            &RED,
+        ))?;
+
+        let mut chart = ChartBuilder::on(&lower)
+            .margin(5)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0f32..end, range(&trace.current))?;
+        chart.configure_mesh().x_desc("Time (s)").y_desc("Current (A)").draw()?;
+        chart.draw_series(LineSeries::new(
+            trace.time.iter().zip(&trace.current).step_by(stride).map(|(t, i)| (*t as f32, *i as f32)),
+            &BLUE,
+        ))?;
+
+        root.present()?;
+
+        Ok(())
+    }
+}
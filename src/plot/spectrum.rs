@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use plotters::{prelude::*, coord::Shift};
+use spwm_generator::{SPWM, Spectrum};
+
+use super::{PlotOptions, PlotResult, draw_on};
+
+/// Bar chart of harmonic magnitudes
+pub struct SpectrumPlot<'a> {
+    pub spectrum:       &'a Spectrum,
+    pub max_freq:       f64,
+    pub log:            bool,
+}
+
+impl SpectrumPlot<'_> {
+    /// write plot next to output file as `<name>_spectrum`
+    pub fn plot(&self, spwm: &SPWM, path: &Path, opts: &PlotOptions) -> PlotResult {
+        let path = opts.path(path, "_spectrum");
+        draw_on!(opts, path, opts.size(600), |root| self.draw(root, spwm))
+    }
+
+    pub fn draw<DB: DrawingBackend>(&self, root: DrawingArea<DB, Shift>, spwm: &SPWM) -> PlotResult
+    where
+        DB::ErrorType: 'static,
+    {
+        let spectrum = self.spectrum;
+        let max_freq = self.max_freq as f32;
+        let bar = (spectrum.fundamental * 0.4) as f32;
+
+        let caption = format!("SPWM Spectrum {} Hz, THD {:.2}%", spwm.sin_freq(), spectrum.thd() * 100.0);
+
+        root.fill(&WHITE)?;
+        let mut builder = ChartBuilder::on(&root);
+        builder
+            .caption(&caption, ("sans-serif", 40).into_font())
+            .margin(5)
+            .x_label_area_size(40)
+            .y_label_area_size(60);
+
+        if self.log {
+            let min_val = 1e-5f32;
+            let mut chart = builder.build_cartesian_2d(0f32..max_freq, (min_val..2f32).log_scale())?;
+            chart.configure_mesh()
+                .x_desc("Frequency (Hz)")
+                .y_desc("Magnitude")
+                .draw()?;
+            chart.draw_series(spectrum.harmonics.iter().map(|h| {
+                let f = h.freq as f32;
+                Rectangle::new([(f - bar, min_val), (f + bar, (h.magnitude as f32).max(min_val))], RED.filled())
+            }))?;
+        }
+        else {
+            let mut chart = builder.build_cartesian_2d(0f32..max_freq, 0f32..1.2f32)?;
+            chart.configure_mesh()
+                .x_desc("Frequency (Hz)")
+                .y_desc("Magnitude")
+                .draw()?;
+            chart.draw_series(spectrum.harmonics.iter().map(|h| {
+                let f = h.freq as f32;
+                Rectangle::new([(f - bar, 0f32), (f + bar, h.magnitude as f32)], RED.filled())
+            }))?;
+        }
+
+        root.present()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use std::path::Path;
+
+use plotters::{prelude::*, coord::Shift};
+use rayon::prelude::*;
+use spwm_generator::{SPWM, DutyCycle, Alignment, SinWave};
+
+use super::{PlotOptions, PlotResult, draw_on};
+
+/// vertical distance between stacked channels
+const CHANNEL_OFFSET: f32 = 1.5;
+/// vertical distance between stacked difference traces, they swing from -1 to 1
+const DIFFERENCE_OFFSET: f32 = 2.5;
+
+/// colors of traces, green and magenta are kept for overlay
+const COLORS: [RGBColor; 6] = [
+    RED,
+    BLUE,
+    RGBColor(255, 140, 0),
+    RGBColor(128, 0, 128),
+    RGBColor(0, 128, 128),
+    BLACK,
+];
+
+/// Single PWM output drawn as its own trace
+#[derive(Debug, Clone)]
+pub struct Channel {
+    pub name:           String,
+    pub table:          Vec<DutyCycle>,
+}
+
+/// Stacked PWM channels, with difference traces between pairs of channels (e.g. line to line voltage)
+#[derive(Debug, Clone, Default)]
+pub struct WavePlot {
+    pub align:          Alignment,
+    pub channels:       Vec<Channel>,
+    /// pairs of channel index, trace is first channel minus second channel
+    pub differences:    Vec<(usize, usize)>,
+    /// draw reference sin wave and carrier on first channel
+    pub overlay:        bool,
+}
+
+impl Channel {
+    /// create new instance
+    pub fn new(name: impl Into<String>, table: Vec<DutyCycle>) -> Self {
+        Self {
+            name: name.into(),
+            table,
+        }
+    }
+}
+
+impl WavePlot {
+    /// write plot as output file with image extension
+    pub fn plot(&self, spwm: &SPWM, path: &Path, opts: &PlotOptions) -> PlotResult {
+        let path = opts.path(path, "");
+        draw_on!(opts, path, opts.size(self.default_height()), |root| self.draw(root, spwm, opts))
+    }
+
+    /// return image height that fits all stacked traces
+    pub fn default_height(&self) -> u32 {
+        let rows = self.channels.len() + self.differences.len();
+        330 + 150 * rows.max(1) as u32
+    }
+
+    pub fn draw<DB: DrawingBackend>(&self, root: DrawingArea<DB, Shift>, spwm: &SPWM, opts: &PlotOptions) -> PlotResult
+    where
+        DB::ErrorType: 'static,
+    {
+        let len = self.channels.iter().map(|c| c.table.len()).min().unwrap_or(0);
+        let (first, last) = opts.window(len);
+        let step = spwm.step() as f32;
+        let (begin, end) = (first as f32 * step, last as f32 * step);
+
+        let caption = format!("SPWM Wave {} Hz", spwm.sin_freq());
+
+        let diff_base = -(self.channels.len() as f32) * CHANNEL_OFFSET;
+        let min_val = if self.differences.is_empty() {
+            -(self.channels.len().max(1) as f32 - 1.0) * CHANNEL_OFFSET
+        }
+        else {
+            diff_base - (self.differences.len() as f32 - 1.0) * DIFFERENCE_OFFSET - 1.0
+        };
+
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&caption, ("sans-serif", 50).into_font())
+            .margin(5)
+            .x_label_area_size(30)
+            .y_label_area_size(30)
+            .build_cartesian_2d(begin..end, min_val..1f32)?;
+
+        chart.configure_mesh().x_desc("Time (s)").draw()?;
+
+        for (idx, channel) in self.channels.iter().enumerate() {
+            let color = COLORS[idx % COLORS.len()];
+            let offset = -(idx as f32) * CHANNEL_OFFSET;
+            chart
+                .draw_series(LineSeries::new(
+                    wave_samples(self.align, spwm, &channel.table[first..last], first, offset),
+                    color,
+                ))?
+                .label(channel.name.as_str())
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        }
+
+        for (idx, (a, b)) in self.differences.iter().enumerate() {
+            let (Some(a), Some(b)) = (self.channels.get(*a), self.channels.get(*b)) else {
+                continue;
+            };
+            let color = COLORS[(self.channels.len() + idx) % COLORS.len()];
+            let offset = diff_base - idx as f32 * DIFFERENCE_OFFSET;
+            chart
+                .draw_series(LineSeries::new(
+                    difference_samples(self.align, spwm, &a.table[first..last], &b.table[first..last], first, offset),
+                    color,
+                ))?
+                .label(format!("{}-{}", a.name, b.name))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        }
+
+        if self.overlay {
+            // reference in duty cycle scale, pulse is high while carrier is below reference
+            let num_of_points = ((last - first) * 16).min(20000);
+            let dt = (end - begin) / num_of_points as f32;
+            let wave = SinWave::new(spwm.sin_freq(), 0.5);
+            chart
+                .draw_series(LineSeries::new(
+                    (0..=num_of_points).map(|x| {
+                        let t = begin + x as f32 * dt;
+                        (t, 0.5 + wave.sample(t as f64) as f32)
+                    }),
+                    &GREEN,
+                ))?
+                .label("Reference")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], GREEN));
+
+            let carrier: Vec<(f32, f32)> = (first..last).flat_map(|idx| {
+                let t = idx as f32 * step;
+                match self.align {
+                    Alignment::Edge => vec![
+                        (t, 0f32),
+                        (t + step, 1f32),
+                        (t + step, 0f32),
+                    ],
+                    Alignment::Center => vec![
+                        (t, 1f32),
+                        (t + step / 2f32, 0f32),
+                        (t + step, 1f32),
+                    ],
+                }
+            }).collect();
+            chart
+                .draw_series(LineSeries::new(
+                    carrier,
+                    &MAGENTA,
+                ))?
+                .label("Carrier")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], MAGENTA));
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        root.present()?;
+
+        Ok(())
+    }
+}
+
+/// return start and end of pulse inside a period of length step
+fn pulse(align: Alignment, step: f32, w: f32) -> (f32, f32) {
+    match align {
+        Alignment::Edge => (0f32, w),
+        Alignment::Center => ((step - w) / 2f32, (step + w) / 2f32),
+    }
+}
+
+/// generate pulse outline of table in seconds, table start at sample `first` and shifted by offset on y axis
+fn wave_samples(align: Alignment, spwm: &SPWM, table: &[DutyCycle], first: usize, offset: f32) -> Vec<(f32, f32)> {
+    let step = spwm.step() as f32;
+    table.par_iter().enumerate().flat_map(|(idx, v)| {
+        let t = (first + idx) as f32 * step;
+        if *v >= spwm.pwm_top() {
+            vec![
+                (t, 1f32 + offset),
+                (t + step, 1f32 + offset),
+            ]
+        }
+        else if *v > 0 {
+            let w = spwm.duty(*v) as f32 * step;
+            let (start, end) = pulse(align, step, w);
+            vec![
+                (t, offset),
+                (t + start, offset),
+                (t + start, 1f32 + offset),
+                (t + end, 1f32 + offset),
+                (t + end, offset),
+                (t + step, offset),
+            ]
+        }
+        else {
+            vec![
+                (t, offset),
+                (t + step, offset),
+            ]
+        }
+    }).collect()
+}
+
+/// generate outline of first table level minus second table level, swing from -1 to 1 around offset
+fn difference_samples(align: Alignment, spwm: &SPWM, a: &[DutyCycle], b: &[DutyCycle], first: usize, offset: f32) -> Vec<(f32, f32)> {
+    let step = spwm.step() as f32;
+    a.par_iter().zip(b.par_iter()).enumerate().flat_map(|(idx, (va, vb))| {
+        let t = (first + idx) as f32 * step;
+        let pa = pulse(align, step, spwm.duty(*va) as f32 * step);
+        let pb = pulse(align, step, spwm.duty(*vb) as f32 * step);
+        let mut edges = [0f32, pa.0, pa.1, pb.0, pb.1, step];
+        edges.sort_by(f32::total_cmp);
+        edges.windows(2)
+            .filter(|e| e[1] > e[0])
+            .flat_map(|e| {
+                let mid = (e[0] + e[1]) / 2f32;
+                let high = |p: (f32, f32)| if mid >= p.0 && mid < p.1 { 1f32 } else { 0f32 };
+                let v = high(pa) - high(pb) + offset;
+                [(t + e[0], v), (t + e[1], v)]
+            })
+            .collect::<Vec<_>>()
+    }).collect()
+}
@@ -0,0 +1,97 @@
+use std::io::{Result, Write};
+
+use spwm_generator::DutyCycle;
+
+/// default number of text rows of chart
+pub const PREVIEW_HEIGHT: usize = 12;
+/// terminal width when stdout is not a terminal and `COLUMNS` is not set
+const DEFAULT_COLUMNS: usize = 80;
+/// width of y axis labels
+const LABEL_WIDTH: usize = 8;
+
+/// dot bits of a braille character, indexed by [column][row]
+const BRAILLE_DOTS: [[u32; 4]; 2] = [
+    [0x01, 0x02, 0x04, 0x40],
+    [0x08, 0x10, 0x20, 0x80],
+];
+
+/// Braille chart of a table, drawn with plain text for terminals
+pub struct Preview<'a> {
+    pub title:          &'a str,
+    pub table:          &'a [DutyCycle],
+    pub pwm_top:        DutyCycle,
+    pub width:          usize,
+    pub height:         usize,
+}
+
+impl<'a> Preview<'a> {
+    /// create new instance that fits terminal width
+    pub fn new(title: &'a str, table: &'a [DutyCycle], pwm_top: DutyCycle) -> Self {
+        Self {
+            title,
+            table,
+            pwm_top,
+            width: terminal_width().saturating_sub(LABEL_WIDTH + 1).max(8),
+            height: PREVIEW_HEIGHT,
+        }
+    }
+
+    pub fn write(&self, buf: &mut impl Write) -> Result<()> {
+        let dots_x = self.width * 2;
+        let dots_y = self.height * 4;
+        let mut cells = vec![0u32; self.width * self.height];
+
+        let len = self.table.len();
+        let top = self.pwm_top.max(1) as f64;
+        // y dot of a value, 0 is top row
+        let to_y = |v: DutyCycle| {
+            let v = (v as f64 / top).clamp(0.0, 1.0);
+            ((1.0 - v) * (dots_y - 1) as f64).round() as usize
+        };
+
+        for x in 0..dots_x {
+            // each dot column shows range of samples that fall in it, first column of a sample is joined to previous sample
+            let begin = x * len / dots_x;
+            let end = ((x + 1) * len / dots_x).max(begin + 1).min(len);
+            let join = if x > 0 && (x - 1) * len / dots_x < begin { begin - 1 } else { begin };
+            let Some(min) = self.table[join.min(end)..end].iter().min() else {
+                continue;
+            };
+            let max = self.table[join.min(end)..end].iter().max().unwrap_or(min);
+            for y in to_y(*max)..=to_y(*min) {
+                cells[(y / 4) * self.width + x / 2] |= BRAILLE_DOTS[x % 2][y % 4];
+            }
+        }
+
+        writeln!(buf, "{}", self.title)?;
+        for row in 0..self.height {
+            let label = if row == 0 {
+                format!("{}", self.pwm_top)
+            }
+            else if row == self.height - 1 {
+                "0".to_string()
+            }
+            else {
+                String::new()
+            };
+            write!(buf, "{:>width$}", label, width = LABEL_WIDTH - 1)?;
+            write!(buf, " \u{2502}")?;
+            for cell in &cells[row * self.width..(row + 1) * self.width] {
+                write!(buf, "{}", char::from_u32(0x2800 + cell).unwrap_or(' '))?;
+            }
+            writeln!(buf)?;
+        }
+        writeln!(buf, "{:>width$}\u{2514}{}", "", "\u{2500}".repeat(self.width), width = LABEL_WIDTH)?;
+        let end = format!("{}", len.saturating_sub(1));
+        writeln!(buf, "{:>width$}0{:>fill$}", "", end, width = LABEL_WIDTH + 1, fill = self.width.saturating_sub(1))
+    }
+}
+
+/// width of terminal attached to stdout, `COLUMNS` is used when stdout is not a terminal
+fn terminal_width() -> usize {
+    terminal_size::terminal_size_of(std::io::stdout())
+        .map(|(width, _)| width.0 as usize)
+        .filter(|width| *width > 0)
+        .or_else(|| std::env::var("COLUMNS").ok().and_then(|x| x.trim().parse().ok()))
+        .unwrap_or(DEFAULT_COLUMNS)
+}
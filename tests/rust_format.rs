@@ -0,0 +1,53 @@
+//! Generate Rust tables with binary and compile them with rustc
+
+use std::{path::PathBuf, process::Command};
+
+fn compile(case: &str, args: &[&str]) {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("rust_format");
+    std::fs::create_dir_all(&dir).unwrap();
+    let src = dir.join(format!("{}.rs", case));
+    let _ = std::fs::remove_file(&src);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_spwm_generator"))
+        .args(args)
+        .arg("-o")
+        .arg(&src)
+        .status()
+        .unwrap();
+    assert!(status.success(), "generate {}", case);
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(rustc)
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "-D", "warnings", "--crate-name", case, "--out-dir"])
+        .arg(&dir)
+        .arg(&src)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "compile {}:\n{}", case, String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn rust_table() {
+    compile("rust_table", &["-m", "rust", "-f", "50", "-c", "10000", "-C"]);
+}
+
+#[test]
+fn rust_hex_inverter() {
+    compile("rust_hex_inverter", &["-m", "rust-hex", "-f", "50", "-c", "10000", "-C", "-t", "1000", "-I", "0.000001"]);
+}
+
+#[test]
+fn rust_fractional_freq() {
+    compile("rust_fractional_freq", &["-m", "rust", "-f", "50.5", "-n", "64", "-s", "0.0001", "-a", "my wave", "-t", "70000"]);
+}
+
+#[test]
+fn rust_static_link_section() {
+    compile("rust_static_link_section", &["-m", "rust", "-f", "60", "-c", "20000", "-C", "-I", "0.000001", "--link_section", ".rodata.spwm"]);
+    compile("rust_static", &["-m", "rust-hex", "-f", "60", "-c", "20000", "-C", "--rust_item", "static"]);
+}
+
+#[test]
+fn rust_name_template() {
+    compile("rust_name_template", &["-m", "rust", "-f", "50.5", "-c", "12500", "-C", "-a", "my wave", "--name_template", "{name}-{freq}Hz @ {carrier_k}k", "-I", "0.000001"]);
+}